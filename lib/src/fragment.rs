@@ -0,0 +1,122 @@
+use crate::Schema;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+// the key `resolve` tags shared options with, which schemas can't set themselves
+const TAG: &str = "$fragment";
+// keys which can sit next to a `$ref` without changing the type generated for it
+const OVERRIDABLE: &[&str] = &["required", "description", "description_localizations"];
+
+// the value of `TAG`
+#[derive(Debug, Deserialize, PartialEq)]
+pub(crate) struct Fragment {
+    /// the file and pointer the option was pulled in from
+    pub id: String,
+    /// the fragment's own description, which the `$ref`s to it may have overridden
+    #[serde(default)]
+    pub description: String,
+}
+
+// a parsed schema file, for resolving references into it
+struct Document {
+    path: Option<PathBuf>,
+    // tells fragments of different documents apart, even those of schemas which weren't read from a file
+    label: String,
+    value: Value,
+}
+
+impl Document {
+    fn read(path: PathBuf, reference: &str) -> Document {
+        let schema = Schema::read(&path)
+            .unwrap_or_else(|e| panic!("couldn't read `{}` referenced by `{}`: {}", path.display(), reference, e));
+        Document { value: schema.format.parse(&schema.contents), label: label(&path), path: Some(path) }
+    }
+}
+
+// the same file can be reached by different relative paths
+fn label(path: &Path) -> String {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf()).display().to_string()
+}
+
+// replaces every `{ "$ref": "file#/json/pointer" }` with the value it points at, where the file is relative to the
+// referencing one and defaults to it; any other keys next to `$ref` are laid over the referenced object. options
+// with choices which come out of a reference unchanged are tagged with where they came from, so that every use of
+// them can share a single enum. `index` is the schema's position in the input, which names it if it has no `path`.
+// also returns every other file the schema pulled in, which build scripts need to watch too
+pub(crate) fn resolve(value: Value, path: Option<&Path>, index: usize) -> (Value, BTreeSet<PathBuf>) {
+    let label = path.map_or_else(|| format!("<schema {}>", index), label);
+    let document = Document { path: path.map(Path::to_path_buf), label, value };
+    let mut resolver = Resolver { stack: Vec::new(), files: BTreeSet::new() };
+    let value = resolver.resolve(document.value.clone(), &document);
+    (value, resolver.files)
+}
+
+struct Resolver {
+    // ids of the fragments currently being resolved, to catch cycles
+    stack: Vec<String>,
+    files: BTreeSet<PathBuf>,
+}
+
+impl Resolver {
+    fn resolve(&mut self, value: Value, document: &Document) -> Value {
+        match value {
+            Value::Array(values) => Value::Array(values.into_iter().map(|x| self.resolve(x, document)).collect()),
+            Value::Object(map) => self.resolve_object(map, document),
+            value => value,
+        }
+    }
+
+    fn resolve_map(&mut self, map: Map<String, Value>, document: &Document) -> Map<String, Value> {
+        map.into_iter().map(|(k, v)| (k, self.resolve(v, document))).collect()
+    }
+
+    fn resolve_object(&mut self, mut map: Map<String, Value>, document: &Document) -> Value {
+        if map.contains_key(TAG) {
+            panic!("`{}` is reserved for options pulled in through `$ref`; remove it from the schema", TAG);
+        }
+        let reference = match map.remove("$ref") {
+            Some(Value::String(reference)) => reference,
+            Some(x) => panic!("`$ref` should be a string, found {}", x),
+            None => return Value::Object(self.resolve_map(map, document)),
+        };
+        let siblings = self.resolve_map(map, document);
+        let (file, pointer) = reference.split_once('#').unwrap_or((&reference, ""));
+        let loaded;
+        let target = if file.is_empty() {
+            document
+        } else {
+            let base = document.path.as_deref().and_then(Path::parent).unwrap_or_else(|| Path::new(""));
+            loaded = Document::read(base.join(file), &reference);
+            self.files.extend(loaded.path.clone());
+            &loaded
+        };
+        let id = format!("{}#{}", target.label, pointer);
+        if self.stack.contains(&id) {
+            panic!("`$ref` cycle: {} -> {}", self.stack.join(" -> "), id);
+        }
+        let fragment = target
+            .value
+            .pointer(pointer)
+            .unwrap_or_else(|| panic!("`$ref` to `{}` doesn't point at anything", reference))
+            .clone();
+        self.stack.push(id.clone());
+        let fragment = self.resolve(fragment, target);
+        self.stack.pop();
+
+        match fragment {
+            Value::Object(mut fragment) => {
+                let shared = siblings.keys().all(|x| OVERRIDABLE.contains(&x.as_str()));
+                let description = fragment.get("description").cloned().unwrap_or_default();
+                fragment.extend(siblings);
+                if shared && fragment.contains_key("choices") {
+                    fragment.insert(TAG.to_string(), serde_json::json!({ "id": id, "description": description }));
+                }
+                Value::Object(fragment)
+            }
+            fragment if siblings.is_empty() => fragment,
+            _ => panic!("`$ref` to `{}` has keys next to it but doesn't point at an object", reference),
+        }
+    }
+}
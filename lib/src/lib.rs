@@ -4,10 +4,14 @@ use serde::{
     de::{Error, Unexpected, Visitor},
     Deserialize, Deserializer,
 };
+use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
+use std::path::{Path, PathBuf};
 
 mod defer;
+mod fragment;
 mod name;
+mod validate;
 
 use defer::Defer;
 use name::Name;
@@ -18,43 +22,377 @@ struct CommandOption {
     r#type: Option<Type>,
     #[serde(deserialize_with = "parse_name")]
     name: Name,
+    /// overrides the identifiers generated from `name`, e.g. for names in other scripts
+    #[serde(default, deserialize_with = "parse_rust_name")]
+    rust_name: Option<Name>,
+    #[serde(default)]
+    description: String,
+    #[serde(default, deserialize_with = "parse_name_localizations")]
+    name_localizations: BTreeMap<String, String>,
+    #[serde(default, deserialize_with = "parse_localizations")]
+    description_localizations: BTreeMap<String, String>,
+    #[serde(default)]
+    required: bool,
+    #[serde(default)]
+    choices: Vec<Choice>,
+    #[serde(default)]
+    min_value: Option<serde_json::Number>,
+    #[serde(default)]
+    max_value: Option<serde_json::Number>,
+    #[serde(default)]
+    min_length: Option<u16>,
+    #[serde(default)]
+    max_length: Option<u16>,
+    #[serde(default)]
+    channel_types: Vec<u64>,
+    #[serde(default)]
+    autocomplete: bool,
     #[serde(default)]
     options: Vec<CommandOption>,
+    #[serde(flatten)]
+    metadata: CommandMetadata,
+    /// where the option was pulled in from by a `$ref`, if it kept the choices of the fragment it points at
+    #[serde(default, rename = "$fragment")]
+    fragment: Option<Box<fragment::Fragment>>,
+}
+
+// who may use a command and where, and which registration it came from when the schema was exported from discord;
+// only meaningful at the top level of a schema
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct CommandMetadata {
+    #[serde(default, deserialize_with = "parse_u64_string")]
+    id: Option<u64>,
+    #[serde(default, deserialize_with = "parse_u64_string")]
+    application_id: Option<u64>,
+    #[serde(default, deserialize_with = "parse_u64_string")]
+    version: Option<u64>,
+    #[serde(default, deserialize_with = "parse_u64_string")]
+    guild_id: Option<u64>,
+    #[serde(default, deserialize_with = "parse_u64_string")]
+    default_member_permissions: Option<u64>,
+    #[serde(default)]
+    dm_permission: Option<bool>,
+    #[serde(default, deserialize_with = "parse_contexts")]
+    contexts: Option<Vec<u64>>,
+    #[serde(default)]
+    nsfw: bool,
+}
+
+// the top level of a schema; `type` there is the kind of command rather than an option type
+#[derive(Debug, PartialEq)]
+enum Command {
+    ChatInput(CommandOption),
+    User(ContextMenu),
+    Message(ContextMenu),
+}
+
+// user and message commands are picked from a right-click menu, so they don't take any options
+#[derive(Debug, Deserialize, PartialEq)]
+struct ContextMenu {
+    #[serde(deserialize_with = "parse_context_menu_name")]
+    name: Name,
+    #[serde(default, deserialize_with = "parse_rust_name")]
+    rust_name: Option<Name>,
+    #[serde(default, deserialize_with = "parse_localizations")]
+    name_localizations: BTreeMap<String, String>,
+    #[serde(flatten)]
+    metadata: CommandMetadata,
+}
+
+impl<'de> Deserialize<'de> for Command {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Command, D::Error> {
+        let mut value = serde_json::Value::deserialize(deserializer)?;
+        let kind = value.as_object_mut().and_then(|x| x.remove("type"));
+        // https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-types
+        let mut command = match kind.as_ref().map(|x| x.as_u64().ok_or_else(|| D::Error::invalid_type(Unexpected::Other("a non-integer"), &"1..=3"))).transpose()? {
+            None | Some(1) => CommandOption::deserialize(value).map(Command::ChatInput),
+            Some(2) => ContextMenu::deserialize(value).map(Command::User),
+            Some(3) => ContextMenu::deserialize(value).map(Command::Message),
+            Some(x) => return Err(D::Error::invalid_value(Unexpected::Unsigned(x), &"1..=3")),
+        }
+        .map_err(D::Error::custom)?;
+        match &mut command {
+            Command::ChatInput(x) => x.apply_rust_names(),
+            Command::User(x) | Command::Message(x) => {
+                if let Some(rust_name) = &x.rust_name {
+                    x.name.rename(rust_name);
+                }
+            }
+        }
+        Ok(command)
+    }
+}
+
+impl Command {
+    pub fn name(&self) -> &Name {
+        match self {
+            Command::ChatInput(x) => &x.name,
+            Command::User(x) | Command::Message(x) => &x.name,
+        }
+    }
+
+    pub fn has_autocomplete(&self) -> bool {
+        matches!(self, Command::ChatInput(x) if x.has_autocomplete())
+    }
+
+    pub fn doc(&self) -> impl ToTokens + '_ {
+        Defer(move || match self {
+            Command::ChatInput(x) => x.doc().into_token_stream(),
+            Command::User(_) | Command::Message(_) => TokenStream::new(),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Choice {
+    #[serde(deserialize_with = "parse_choice_name")]
+    name: Name,
+    #[serde(default, deserialize_with = "parse_localizations")]
+    name_localizations: BTreeMap<String, String>,
+    value: serde_json::Value,
 }
 
 #[derive(Debug, PartialEq, Eq)]
 enum Type {
     String,
     Bool,
-    U64,
+    Integer,
+    Number,
+    User,
+    Channel,
+    Role,
+    Mentionable,
+    Attachment,
     Subcommand,
+    SubcommandGroup,
 }
 
 impl CommandOption {
+    fn apply_rust_names(&mut self) {
+        if let Some(rust_name) = &self.rust_name {
+            self.name.rename(rust_name);
+        }
+        for x in &mut self.options {
+            x.apply_rust_names();
+        }
+    }
+
+    /// every option in the tree whose enum is generated at the top level, see `is_shared_enum`
+    fn shared_enums(&self) -> Vec<&CommandOption> {
+        let nested = self.options.iter().flat_map(CommandOption::shared_enums);
+        Some(self).filter(|x| x.is_shared_enum()).into_iter().chain(nested).collect()
+    }
+
+    /// options with choices get their own enum, named after the option
     pub fn as_type(&self) -> Defer<&str> {
+        if self.choices.is_empty() {
+            self.as_wire_type()
+        } else {
+            self.name.camel()
+        }
+    }
+
+    pub fn as_wire_type(&self) -> Defer<&str> {
         match self.r#type.as_ref().unwrap() {
             Type::String => Defer("String"),
             Type::Bool => Defer("bool"),
-            Type::U64 => Defer("u64"),
-            Type::Subcommand => unreachable!("tried to print type of subcommand"),
+            Type::Integer => Defer("i64"),
+            Type::Number => Defer("f64"),
+            Type::User => Defer("UserId"),
+            Type::Channel => Defer("ChannelId"),
+            Type::Role => Defer("RoleId"),
+            Type::Mentionable => Defer("MentionableId"),
+            Type::Attachment => Defer("AttachmentId"),
+            Type::Subcommand | Type::SubcommandGroup => unreachable!("tried to print type of subcommand"),
         }
     }
+
+    /// the schema's description as a doc comment on whichever item this option generates
+    pub fn doc(&self) -> impl ToTokens + '_ {
+        Defer((!self.description.is_empty(), move || {
+            let description = &self.description;
+            quote! { #[doc = #description] }
+        }))
+    }
+
+    /// whether `as_type` names one of the types generated once alongside `Interaction` rather than a std type
+    pub fn is_shared_type(&self) -> bool {
+        self.is_shared_enum()
+            || self.choices.is_empty()
+                && matches!(
+                self.r#type,
+                    Some(Type::User | Type::Channel | Type::Role | Type::Mentionable | Type::Attachment)
+                )
+    }
+
+    /// the doc comment on the enum for the option's choices; shared ones take the fragment's own description rather
+    /// than whichever `$ref` to it happens to be generated
+    pub fn enum_doc(&self) -> impl ToTokens + '_ {
+        let description = self.fragment.as_ref().map_or(&self.description, |x| &x.description);
+        Defer((!description.is_empty(), move || quote! { #[doc = #description] }))
+    }
+
+    /// whether the enum for the option's choices is generated once alongside `Interaction`, for every option
+    /// referencing the same fragment to share
+    pub fn is_shared_enum(&self) -> bool {
+        self.fragment.is_some() && !self.choices.is_empty()
+    }
+
+    /// the entries of `Resolved` which an option's id can be looked up in
+    pub fn resolved_entities(&self) -> &'static [ResolvedEntity] {
+        if !self.choices.is_empty() {
+            return &[];
+        }
+        match self.r#type {
+            Some(Type::User) => &[USER, MEMBER],
+            Some(Type::Channel) => &[CHANNEL],
+            Some(Type::Role) => &[ROLE],
+            Some(Type::Mentionable) => &[
+                ResolvedEntity { conversion: Some("as_user"), ..USER },
+                ResolvedEntity { conversion: Some("as_user"), ..MEMBER },
+                ResolvedEntity { conversion: Some("as_role"), ..ROLE },
+            ],
+            Some(Type::Attachment) => &[ATTACHMENT],
+            _ => &[],
+        }
+    }
+
+    /// the type of the generated struct field; options which aren't required are wrapped in `Option`
+    pub fn as_field_type(&self) -> impl ToTokens + '_ {
+        Defer(move || {
+            let kind = self.as_type();
+            if self.required {
+                quote! { #kind }
+            } else {
+                quote! { Option<#kind> }
+            }
+        })
+    }
+}
+
+struct ResolvedEntity {
+    /// appended to the option name to get the accessor name
+    suffix: &'static str,
+    /// field of `Resolved` holding the map
+    map: &'static str,
+    kind: &'static str,
+    /// key type of the map
+    id: &'static str,
+    /// method converting the option's id into the map's key type, if they differ
+    conversion: Option<&'static str>,
+}
+
+// https://discord.com/developers/docs/resources/channel#channel-object-channel-types
+const CHANNEL_TYPES: &[(u64, &str)] = &[
+    (0, "GuildText"),
+    (1, "Dm"),
+    (2, "GuildVoice"),
+    (3, "GroupDm"),
+    (4, "GuildCategory"),
+    (5, "GuildAnnouncement"),
+    (10, "AnnouncementThread"),
+    (11, "PublicThread"),
+    (12, "PrivateThread"),
+    (13, "GuildStageVoice"),
+    (14, "GuildDirectory"),
+    (15, "GuildForum"),
+    (16, "GuildMedia"),
+];
+
+// https://discord.com/developers/docs/interactions/receiving-and-responding#interaction-object-interaction-context-types
+const CONTEXTS: &[(u64, &str)] = &[(0, "Guild"), (1, "BotDm"), (2, "PrivateChannel")];
+
+// https://discord.com/developers/docs/topics/permissions#permissions-bitwise-permission-flags
+const PERMISSIONS: &[(u32, &str)] = &[
+    (0, "CREATE_INSTANT_INVITE"),
+    (1, "KICK_MEMBERS"),
+    (2, "BAN_MEMBERS"),
+    (3, "ADMINISTRATOR"),
+    (4, "MANAGE_CHANNELS"),
+    (5, "MANAGE_GUILD"),
+    (6, "ADD_REACTIONS"),
+    (7, "VIEW_AUDIT_LOG"),
+    (8, "PRIORITY_SPEAKER"),
+    (9, "STREAM"),
+    (10, "VIEW_CHANNEL"),
+    (11, "SEND_MESSAGES"),
+    (12, "SEND_TTS_MESSAGES"),
+    (13, "MANAGE_MESSAGES"),
+    (14, "EMBED_LINKS"),
+    (15, "ATTACH_FILES"),
+    (16, "READ_MESSAGE_HISTORY"),
+    (17, "MENTION_EVERYONE"),
+    (18, "USE_EXTERNAL_EMOJIS"),
+    (19, "VIEW_GUILD_INSIGHTS"),
+    (20, "CONNECT"),
+    (21, "SPEAK"),
+    (22, "MUTE_MEMBERS"),
+    (23, "DEAFEN_MEMBERS"),
+    (24, "MOVE_MEMBERS"),
+    (25, "USE_VAD"),
+    (26, "CHANGE_NICKNAME"),
+    (27, "MANAGE_NICKNAMES"),
+    (28, "MANAGE_ROLES"),
+    (29, "MANAGE_WEBHOOKS"),
+    (30, "MANAGE_GUILD_EXPRESSIONS"),
+    (31, "USE_APPLICATION_COMMANDS"),
+    (32, "REQUEST_TO_SPEAK"),
+    (33, "MANAGE_EVENTS"),
+    (34, "MANAGE_THREADS"),
+    (35, "CREATE_PUBLIC_THREADS"),
+    (36, "CREATE_PRIVATE_THREADS"),
+    (37, "USE_EXTERNAL_STICKERS"),
+    (38, "SEND_MESSAGES_IN_THREADS"),
+    (39, "USE_EMBEDDED_ACTIVITIES"),
+    (40, "MODERATE_MEMBERS"),
+    (41, "VIEW_CREATOR_MONETIZATION_ANALYTICS"),
+    (42, "USE_SOUNDBOARD"),
+    (43, "CREATE_GUILD_EXPRESSIONS"),
+    (44, "CREATE_EVENTS"),
+    (45, "USE_EXTERNAL_SOUNDS"),
+    (46, "SEND_VOICE_MESSAGES"),
+    (49, "SEND_POLLS"),
+    (50, "USE_EXTERNAL_APPS"),
+];
+
+fn channel_type(value: u64) -> TokenStream {
+    match CHANNEL_TYPES.iter().find(|(x, _)| *x == value) {
+        Some((_, variant)) => {
+            let variant = Defer(*variant);
+            quote! { ChannelType::#variant }
+        }
+        None => quote! { ChannelType::Unknown(#value) },
+    }
 }
 
+const USER: ResolvedEntity = ResolvedEntity { suffix: "user", map: "users", kind: "User", id: "UserId", conversion: None };
+const MEMBER: ResolvedEntity = ResolvedEntity { suffix: "member", map: "members", kind: "PartialMember", id: "UserId", conversion: None };
+const ROLE: ResolvedEntity = ResolvedEntity { suffix: "role", map: "roles", kind: "Role", id: "RoleId", conversion: None };
+const CHANNEL: ResolvedEntity = ResolvedEntity { suffix: "channel", map: "channels", kind: "PartialChannel", id: "ChannelId", conversion: None };
+const ATTACHMENT: ResolvedEntity = ResolvedEntity { suffix: "attachment", map: "attachments", kind: "Attachment", id: "AttachmentId", conversion: None };
+const MESSAGE: ResolvedEntity = ResolvedEntity { suffix: "message", map: "messages", kind: "Message", id: "MessageId", conversion: None };
+
 fn parse_type<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Type>, D::Error> {
     struct TypeVisitor;
     impl<'de> Visitor<'de> for TypeVisitor {
         type Value = Option<Type>;
         fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            f.write_str("1..=9")
+            f.write_str("1..=11")
         }
         // https://discord.com/developers/docs/interactions/slash-commands#data-models-and-types
         fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
             match v {
-                4 => Ok(Some(Type::U64)),
+                4 => Ok(Some(Type::Integer)),
+                10 => Ok(Some(Type::Number)),
+                11 => Ok(Some(Type::Attachment)),
                 5 => Ok(Some(Type::Bool)),
-                3 | 6..=9 => Ok(Some(Type::String)),
-                1 | 2 => Ok(Some(Type::Subcommand)),
+                3 => Ok(Some(Type::String)),
+                6 => Ok(Some(Type::User)),
+                7 => Ok(Some(Type::Channel)),
+                8 => Ok(Some(Type::Role)),
+                9 => Ok(Some(Type::Mentionable)),
+                1 => Ok(Some(Type::Subcommand)),
+                2 => Ok(Some(Type::SubcommandGroup)),
                 _ => Err(E::invalid_value(Unexpected::Unsigned(v), &self)),
             }
         }
@@ -69,7 +407,7 @@ fn parse_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Err
 
         // https://discord.com/developers/docs/interactions/slash-commands#registering-a-command
         fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            f.write_str("a string matching the regex `^[\\w-]{1,32}$`")
+            f.write_str("a lowercase name of 1-32 letters or digits from any script, `-` or `_`")
         }
         fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
             Name::new(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
@@ -78,24 +416,730 @@ fn parse_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Err
     deserializer.deserialize_str(NameVisitor)
 }
 
-fn structify_data(input: &CommandOption) -> impl ToTokens + '_ {
+// only ascii names can be used as identifiers as is
+fn parse_rust_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Name>, D::Error> {
+    struct RustNameVisitor;
+    impl<'de> Visitor<'de> for RustNameVisitor {
+        type Value = Option<Name>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string matching the regex `^[a-z0-9_-]{1,32}$`")
+        }
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.bytes()
+                .all(|b| b.is_ascii())
+                .then(|| Name::new(v))
+                .flatten()
+                .map(Some)
+                .ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+    deserializer.deserialize_str(RustNameVisitor)
+}
+
+// https://discord.com/developers/docs/reference#locales
+const LOCALES: &[(&str, &str)] = &[
+    ("id", "Id"),
+    ("da", "Da"),
+    ("de", "De"),
+    ("en-GB", "EnGb"),
+    ("en-US", "EnUs"),
+    ("es-ES", "EsEs"),
+    ("es-419", "Es419"),
+    ("fr", "Fr"),
+    ("hr", "Hr"),
+    ("it", "It"),
+    ("lt", "Lt"),
+    ("hu", "Hu"),
+    ("nl", "Nl"),
+    ("no", "No"),
+    ("pl", "Pl"),
+    ("pt-BR", "PtBr"),
+    ("ro", "Ro"),
+    ("fi", "Fi"),
+    ("sv-SE", "SvSe"),
+    ("vi", "Vi"),
+    ("tr", "Tr"),
+    ("cs", "Cs"),
+    ("el", "El"),
+    ("bg", "Bg"),
+    ("ru", "Ru"),
+    ("uk", "Uk"),
+    ("hi", "Hi"),
+    ("th", "Th"),
+    ("zh-CN", "ZhCn"),
+    ("ja", "Ja"),
+    ("zh-TW", "ZhTw"),
+    ("ko", "Ko"),
+];
+
+fn locale_variant(locale: &str) -> Defer<&'static str> {
+    Defer(LOCALES.iter().find(|(x, _)| *x == locale).expect("locales to be validated while parsing").1)
+}
+
+// discord sends snowflakes and permissions as strings since they don't fit in a javascript number
+fn parse_u64_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    struct U64StringVisitor;
+    impl<'de> Visitor<'de> for U64StringVisitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a string of digits, or null")
+        }
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            v.parse().map(Some).map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+        }
+        fn visit_u64<E: Error>(self, v: u64) -> Result<Self::Value, E> {
+            Ok(Some(v))
+        }
+        fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+            Ok(None)
+        }
+    }
+    deserializer.deserialize_any(U64StringVisitor)
+}
+
+fn parse_contexts<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u64>>, D::Error> {
+    let contexts = Option::<Vec<u64>>::deserialize(deserializer)?;
+    match contexts.iter().flatten().find(|&&x| CONTEXTS.iter().all(|&(context, _)| context != x)) {
+        Some(&x) => Err(D::Error::invalid_value(Unexpected::Unsigned(x), &"an interaction context type (0..=2)")),
+        None => Ok(contexts),
+    }
+}
+
+fn parse_localizations<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error> {
+    // discord returns `null` rather than omitting the field
+    let localizations = Option::<BTreeMap<String, String>>::deserialize(deserializer)?.unwrap_or_default();
+    match localizations.keys().find(|x| LOCALES.iter().all(|(locale, _)| locale != x)) {
+        Some(x) => Err(D::Error::invalid_value(Unexpected::Str(x), &"a locale supported by discord")),
+        None => Ok(localizations),
+    }
+}
+
+// localized names follow the same rules as the names themselves
+fn parse_name_localizations<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<String, String>, D::Error> {
+    let localizations = parse_localizations(deserializer)?;
+    match localizations.values().find(|x| !name::validate(x)) {
+        Some(x) => Err(D::Error::invalid_value(Unexpected::Str(x), &"a lowercase name of 1-32 letters or digits from any script, `-` or `_`")),
+        None => Ok(localizations),
+    }
+}
+
+// an expression which evaluates to the translation for `locale`, falling back to `default` for locales without one
+fn localized_match(default: &str, localizations: &BTreeMap<String, String>) -> TokenStream {
+    if localizations.is_empty() {
+        return quote! { { let _ = locale; #default } };
+    }
+    let locales = localizations.keys().map(|x| locale_variant(x));
+    let values = localizations.values();
+    quote! {
+        match locale {
+            #(Locale::#locales => #values,)*
+            _ => #default,
+        }
+    }
+}
+
+// associated constants describing who may use a command and where, and which registration it came from, mirroring
+// the schema
+fn generate_metadata_constants(metadata: &CommandMetadata) -> impl ToTokens + '_ {
+    Defer(move || {
+        let permissions = match metadata.default_member_permissions {
+            Some(x) => quote! { Some(super::Permissions(#x)) },
+            None => quote! { None },
+        };
+        // discord defaults to allowing commands in dms
+        let dm_permission = metadata.dm_permission.unwrap_or(true);
+        let contexts = match &metadata.contexts {
+            Some(contexts) => {
+                let variants = contexts
+                    .iter()
+                    .map(|&x| Defer(CONTEXTS.iter().find(|&&(context, _)| context == x).unwrap().1));
+                quote! { Some(&[#(super::InteractionContextType::#variants),*]) }
+            }
+            None => quote! { None },
+        };
+        let nsfw = metadata.nsfw;
+        let snowflake = |x: Option<u64>| match x {
+            Some(x) => quote! { Some(super::Snowflake(#x)) },
+            None => quote! { None },
+        };
+        let (id, application_id, version, guild_id) = (
+            snowflake(metadata.id),
+            snowflake(metadata.application_id),
+            snowflake(metadata.version),
+            snowflake(metadata.guild_id),
+        );
+        quote! {
+            /// the registration this was generated from, if the schema was exported from discord
+            pub const ID: Option<super::Snowflake> = #id;
+            pub const APPLICATION_ID: Option<super::Snowflake> = #application_id;
+            /// changes whenever the command is updated on discord's side
+            pub const VERSION: Option<super::Snowflake> = #version;
+            /// `None` for global commands
+            pub const GUILD_ID: Option<super::Snowflake> = #guild_id;
+            /// permissions a member needs to use this command by default; `None` allows everyone
+            pub const DEFAULT_MEMBER_PERMISSIONS: Option<super::Permissions> = #permissions;
+            pub const DM_PERMISSION: bool = #dm_permission;
+            /// contexts the command may be used in; `None` leaves it up to discord
+            pub const CONTEXTS: Option<&'static [super::InteractionContextType]> = #contexts;
+            pub const NSFW: bool = #nsfw;
+        }
+    })
+}
+
+// `localized_name` and `localized_description` for an option, prefixed with `prefix` if given
+fn generate_localized_fns<'a>(option: &'a CommandOption, prefix: Option<&'a str>) -> impl ToTokens + 'a {
     Defer(move || {
+        let ident = |x: &str| match prefix {
+            Some(prefix) => proc_macro2::Ident::new(&format!("{}_{}", prefix, x), proc_macro2::Span::call_site()),
+            None => proc_macro2::Ident::new(x, proc_macro2::Span::call_site()),
+        };
+        let name_ident = ident("localized_name");
+        let description_ident = ident("localized_description");
+        let name = localized_match(option.name.wire(), &option.name_localizations);
+        let description = localized_match(&option.description, &option.description_localizations);
+        quote! {
+            #[allow(unreachable_patterns)]
+            pub const fn #name_ident(locale: Locale) -> &'static str {
+                #name
+            }
+            #[allow(unreachable_patterns)]
+            pub const fn #description_ident(locale: Locale) -> &'static str {
+                #description
+            }
+        }
+    })
+}
+
+fn parse_context_menu_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Error> {
+    struct ContextMenuNameVisitor;
+    impl<'de> Visitor<'de> for ContextMenuNameVisitor {
+        type Value = Name;
+
+        // https://discord.com/developers/docs/interactions/application-commands#application-command-naming
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a name of 1-32 characters containing at least one ascii letter or digit")
+        }
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            Name::context_menu(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+    deserializer.deserialize_str(ContextMenuNameVisitor)
+}
+
+fn parse_choice_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Name, D::Error> {
+    struct ChoiceNameVisitor;
+    impl<'de> Visitor<'de> for ChoiceNameVisitor {
+        type Value = Name;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a choice name of 1-100 characters containing at least one ascii letter or digit")
+        }
+        fn visit_str<E: Error>(self, v: &str) -> Result<Self::Value, E> {
+            Name::choice(v).ok_or_else(|| E::invalid_value(Unexpected::Str(v), &self))
+        }
+    }
+    deserializer.deserialize_str(ChoiceNameVisitor)
+}
+
+fn choice_literal(kind: &Type, value: &serde_json::Value) -> proc_macro2::Literal {
+    match value {
+        serde_json::Value::String(s) => proc_macro2::Literal::string(s),
+        serde_json::Value::Number(n) => number_literal(kind, n),
+        _ => panic!("choice values must be strings or numbers, found {}", value),
+    }
+}
+
+fn number_literal(kind: &Type, n: &serde_json::Number) -> proc_macro2::Literal {
+    use proc_macro2::Literal;
+    // numbers can be written as integers in the schema but still need to compare against an f64
+    if kind == &Type::Number {
+        return Literal::f64_unsuffixed(n.as_f64().expect("serde_json numbers are always representable as f64"));
+    }
+    match (n.as_u64(), n.as_i64(), n.as_f64()) {
+        (Some(v), _, _) => Literal::u64_unsuffixed(v),
+        (None, Some(v), _) => Literal::i64_unsuffixed(v),
+        (None, None, Some(v)) => Literal::f64_unsuffixed(v),
+        _ => unreachable!("serde_json numbers are always representable as one of u64, i64 or f64"),
+    }
+}
+
+fn generate_choice_enum(option: &CommandOption) -> impl ToTokens + '_ {
+    Defer((!option.choices.is_empty(), move || {
+        let enum_ident = option.name.camel();
+        let doc = option.enum_doc();
+        let wire_type = option.as_wire_type();
+        let option_name = option.name.wire();
+        let variants = option.choices.iter().map(|x| x.name.camel());
+        let variants2 = option.choices.iter().map(|x| x.name.camel());
+        let variants3 = option.choices.iter().map(|x| x.name.camel());
+        let kind = option.r#type.as_ref().unwrap();
+        let values = option.choices.iter().map(|x| choice_literal(kind, &x.value));
+        let values2 = option.choices.iter().map(|x| choice_literal(kind, &x.value));
+        let variants4 = option.choices.iter().map(|x| x.name.camel());
+        let localized_names = option.choices.iter().map(|x| localized_match(x.name.wire(), &x.name_localizations));
+        quote! {
+            #doc
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum #enum_ident {
+                #(#variants,)*
+            }
+
+            impl #enum_ident {
+                #[allow(unreachable_patterns)]
+                pub const fn localized_name(self, locale: Locale) -> &'static str {
+                    match self {
+                        #(Self::#variants4 => #localized_names,)*
+                    }
+                }
+            }
+
+            impl serde::Serialize for #enum_ident {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    match self {
+                        #(Self::#variants2 => serde::Serialize::serialize(&#values, serializer),)*
+                    }
+                }
+            }
+
+            impl<'de> serde::Deserialize<'de> for #enum_ident {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<#enum_ident, D::Error> {
+                    let value = <#wire_type as serde::Deserialize>::deserialize(deserializer)?;
+                    #(if value == #values2 { return Ok(Self::#variants3); })*
+                    Err(<D::Error as serde::de::Error>::custom(format!("`{}` is not a valid choice for `{}`", value, #option_name)))
+                }
+            }
+        }
+    }))
+}
+
+// validation for a single option value `v`; runs inside the `visit_seq` of the generated options deserializer
+fn generate_checks(option: &CommandOption) -> impl ToTokens + '_ {
+    // options with choices are already checked by their own enum
+    Defer((option.choices.is_empty(), move || {
+        let name = option.name.wire();
+        let kind = option.r#type.as_ref().unwrap();
+        let mut checks = Vec::new();
+        if let Some(min) = option.min_value.as_ref().map(|x| number_literal(kind, x)) {
+            checks.push(quote! {
+                if v < #min {
+                    return Err(<A::Error as serde::de::Error>::custom(format!("`{}` must be at least {}, found {}", #name, #min, v)));
+                }
+            });
+        }
+        if let Some(max) = option.max_value.as_ref().map(|x| number_literal(kind, x)) {
+            checks.push(quote! {
+                if v > #max {
+                    return Err(<A::Error as serde::de::Error>::custom(format!("`{}` must be at most {}, found {}", #name, #max, v)));
+                }
+            });
+        }
+        if option.min_length.is_some() || option.max_length.is_some() {
+            checks.push(quote! { let len = v.chars().count(); });
+        }
+        if option.min_length.is_some() {
+            let constant = length_constant(option, "MIN");
+            checks.push(quote! {
+                if len < Options::#constant {
+                    return Err(<A::Error as serde::de::Error>::custom(format!("`{}` must be at least {} characters long, found {}", #name, Options::#constant, len)));
+                }
+            });
+        }
+        if option.max_length.is_some() {
+            let constant = length_constant(option, "MAX");
+            checks.push(quote! {
+                if len > Options::#constant {
+                    return Err(<A::Error as serde::de::Error>::custom(format!("`{}` must be at most {} characters long, found {}", #name, Options::#constant, len)));
+                }
+            });
+        }
+        quote! { #(#checks)* }
+    }))
+}
+
+impl CommandOption {
+    /// whether this option is a channel option limited to certain channel types
+    pub fn has_channel_types(&self) -> bool {
+        self.r#type == Some(Type::Channel) && !self.channel_types.is_empty()
+    }
+
+    /// whether this command or any of its subcommands has a channel option limited to certain channel types
+    pub fn restricts_channels(&self) -> bool {
+        self.has_channel_types() || self.options.iter().any(CommandOption::restricts_channels)
+    }
+
+    /// whether this command or any of its subcommands has an option with autocomplete
+    pub fn has_autocomplete(&self) -> bool {
+        self.autocomplete || self.options.iter().any(CommandOption::has_autocomplete)
+    }
+}
+
+// picks the variant of `enum_ident` by the command's name; `variants` pairs each command's name with the type it
+// deserializes into, and anything else falls through to `Other`
+fn generate_command_dispatch<'a>(
+    enum_ident: Defer<&'a str>,
+    variants: impl Iterator<Item = (&'a Name, TokenStream)>,
+) -> impl ToTokens + 'a {
+    let (names, types): (Vec<_>, Vec<_>) = variants.unzip();
+    Defer(move || {
+        let wire_names = names.iter().map(|x| x.wire());
+        let camels = names.iter().map(|x| x.camel());
+        quote! {
+            impl<'de> serde::Deserialize<'de> for #enum_ident {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<#enum_ident, D::Error> {
+                    #[derive(serde::Deserialize)]
+                    struct Other {
+                        id: String,
+                        name: String,
+                    }
+
+                    let value = serde_json::Value::deserialize(deserializer)?;
+                    Ok(
+                        match value
+                            .get("name")
+                            .and_then(serde_json::Value::as_str)
+                            .ok_or_else(|| <D::Error as serde::de::Error>::custom("name field is either missing or not a string"))?
+                        {
+                            #(#wire_names => #enum_ident::#camels(
+                                <#types as serde::Deserialize>::deserialize(value).map_err(<D::Error as serde::de::Error>::custom)?,
+                            ),)*
+                            _ => {
+                                let other = Other::deserialize(value).map_err(<D::Error as serde::de::Error>::custom)?;
+                                #enum_ident::Other { id: other.id, name: other.name }
+                            }
+                        },
+                    )
+                }
+            }
+        }
+    })
+}
+
+// subcommands without options don't get a module of their own to hold `localized_name` and
+// `localized_description`, so they go on the enum holding them instead, prefixed with the subcommand's name
+fn generate_unit_localized_fns<'a>(subcommands: &'a [&'a CommandOption]) -> impl ToTokens + 'a {
+    Defer(move || {
+        let fns = subcommands
+            .iter()
+            .filter(|x| x.options.is_empty())
+            .map(|x| generate_localized_fns(x, Some(x.name.snake().0)));
+        quote! { #(#fns)* }
+    })
+}
+
+// a variant named after a subcommand holding its options, or a unit variant if it doesn't take any; discord may
+// send those with an empty list of options or none at all, which `parse_empty` accepts either way
+fn generate_options_variant<'a>(option: &'a CommandOption, parse_empty: &'a str) -> impl ToTokens + 'a {
+    Defer(move || {
+        let doc = option.doc();
+        let rename = option.name.serde_rename();
+        let camel = option.name.camel();
+        if option.options.is_empty() {
+            quote! {
+                #doc
+                #rename
+                #[serde(deserialize_with = #parse_empty)]
+                #camel
+            }
+        } else {
+            let snake = option.name.snake();
+            quote! { #doc #rename #camel(#snake::Options) }
+        }
+    })
+}
+
+// e.g. `CtfAutocomplete` for `ctf`
+fn autocomplete_ident(name: &Name) -> proc_macro2::Ident {
+    proc_macro2::Ident::new(&format!("{}Autocomplete", name.camel()), proc_macro2::Span::call_site())
+}
+
+// the options of an autocomplete interaction haven't been submitted yet, so every field is optional and the focused
+// option's value is whatever the user has typed so far
+fn generate_partial_options(input: &CommandOption) -> impl ToTokens + '_ {
+    Defer((input.has_autocomplete(), move || {
+        let names = input.options.iter().map(|x| x.name.snake());
         let kinds = input.options.iter().map(|x| x.as_type());
+        let docs = input.options.iter().map(|x| x.doc());
+        let idents = input.options.iter().map(|x| x.name.snake());
+        let kinds2 = input.options.iter().map(|x| x.as_type());
+        let idents2 = input.options.iter().map(|x| x.name.snake());
+        let idents3 = input.options.iter().map(|x| x.name.snake());
+        let wire_names = input.options.iter().map(|x| x.name.wire()).collect::<Vec<_>>();
+        let focusable = input.options.iter().filter(|x| x.autocomplete);
+        let focus_variants = focusable.clone().map(|x| x.name.camel()).collect::<Vec<_>>();
+        let focus_docs = focusable.clone().map(|x| x.doc());
+        let focus_names = focusable.map(|x| x.name.wire());
+        quote! {
+            #[derive(serde::Serialize, Debug, Clone, PartialEq, Eq)]
+            pub enum Focused {
+                #(#focus_docs #focus_variants(String),)*
+            }
+
+            #[derive(serde::Serialize, Debug)]
+            pub struct PartialOptions {
+                #(#docs pub #names: Option<#kinds>,)*
+                pub focused: Focused,
+            }
+
+            impl<'de> serde::Deserialize<'de> for PartialOptions {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<PartialOptions, D::Error> {
+                    #[derive(serde::Deserialize)]
+                    struct Property {
+                        name: String,
+                        value: serde_json::Value,
+                        #[serde(default)]
+                        focused: bool,
+                    }
+
+                    #[derive(Default)]
+                    struct Partial {
+                        #(#idents: Option<#kinds2>,)*
+                    }
+
+                    let mut partial = Partial::default();
+                    let mut focused = None;
+                    for property in <Vec<Property> as serde::Deserialize>::deserialize(deserializer)? {
+                        if property.focused {
+                            // numeric options are sent as strings while they're being typed
+                            let value = match property.value {
+                                serde_json::Value::String(s) => s,
+                                v => v.to_string(),
+                            };
+                            focused = Some(match property.name.as_str() {
+                                #(#focus_names => Focused::#focus_variants(value),)*
+                                name => return Err(<D::Error as serde::de::Error>::custom(format!("`{}` can't be focused since it doesn't have autocomplete", name))),
+                            });
+                        } else {
+                            match property.name.as_str() {
+                                #(#wire_names => partial.#idents2 = Some(serde_json::from_value(property.value).map_err(<D::Error as serde::de::Error>::custom)?),)*
+                                name => return Err(<D::Error as serde::de::Error>::unknown_field(name, &[#(#wire_names),*])),
+                            }
+                        }
+                    }
+                    Ok(PartialOptions {
+                        #(#idents3: partial.#idents3,)*
+                        focused: focused.ok_or_else(|| <D::Error as serde::de::Error>::custom("none of the options are focused"))?,
+                    })
+                }
+            }
+        }
+    }))
+}
+
+// checking `channel_types` needs the resolved channel, which isn't available until the options have been parsed;
+// instead, each level gets a `check_resolved` which is run once the whole command has been deserialized
+fn generate_channel_type_checks<'a>(
+    input: &'a CommandOption,
+    resolved_struct: Option<&'a str>,
+) -> impl ToTokens + 'a {
+    Defer((resolved_struct.is_none() && input.restricts_channels(), move || {
+        let checks = input.options.iter().filter(|x| x.has_channel_types()).map(|opt| {
+            let name = opt.name.wire();
+            let accessor = proc_macro2::Ident::new(&format!("{}_{}", opt.name.snake(), CHANNEL.suffix), proc_macro2::Span::call_site());
+            let constant = channel_types_constant(opt);
+            quote! {
+                if let Some(channel) = self.#accessor(resolved) {
+                    if !Self::#constant.contains(&channel.r#type) {
+                        return Err(format!("`{}` must be one of {:?}, found {:?}", #name, Self::#constant, channel.r#type));
+                    }
+                }
+            }
+        });
+        quote! {
+            impl Options {
+                pub(crate) fn check_resolved(&self, resolved: &Resolved) -> Result<(), String> {
+                    #(#checks)*
+                    Ok(())
+                }
+            }
+        }
+    }))
+}
+
+// dispatches `check_resolved` to whichever variant is active; `variants` pairs each variant with whether it has checks
+fn generate_check_dispatch<'a>(
+    enum_ident: Defer<&'a str>,
+    variants: impl Iterator<Item = (bool, Defer<&'a str>)>,
+    resolved_type: impl ToTokens + 'a,
+    resolved_struct: Option<&'a str>,
+) -> impl ToTokens + 'a {
+    let checked = variants
+        .filter(|&(checked, _)| checked)
+        .map(|(_, variant)| variant)
+        .collect::<Vec<_>>();
+    Defer((resolved_struct.is_none() && !checked.is_empty(), move || {
+        let checked = &checked;
+        quote! {
+            impl #enum_ident {
+                pub(crate) fn check_resolved(&self, resolved: &#resolved_type) -> Result<(), String> {
+                    #[allow(unreachable_patterns)]
+                    match self {
+                        #(Self::#checked(x) => x.check_resolved(resolved),)*
+                        _ => Ok(()),
+                    }
+                }
+            }
+        }
+    }))
+}
+
+// e.g. `CHANNEL_CHANNEL_TYPES` for the `channel_types` of an option named `channel`
+fn channel_types_constant(option: &CommandOption) -> proc_macro2::Ident {
+    proc_macro2::Ident::new(
+        &format!("{}_CHANNEL_TYPES", option.name.snake().to_string().to_ascii_uppercase()),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+// e.g. `FLAG_MAX_LENGTH` for the `max_length` of an option named `flag`
+fn length_constant(option: &CommandOption, bound: &str) -> proc_macro2::Ident {
+    proc_macro2::Ident::new(
+        &format!("{}_{}_LENGTH", option.name.snake().to_string().to_ascii_uppercase(), bound),
+        proc_macro2::Span::call_site(),
+    )
+}
+
+fn generate_constants(input: &CommandOption) -> impl ToTokens + '_ {
+    let has_constants = input
+        .options
+        .iter()
+        .any(|x| x.min_length.is_some() || x.max_length.is_some() || x.has_channel_types());
+    Defer((has_constants, move || {
+        let length_constants = input.options.iter().flat_map(|opt| {
+            let min = opt.min_length.map(|x| (length_constant(opt, "MIN"), x as usize));
+            let max = opt.max_length.map(|x| (length_constant(opt, "MAX"), x as usize));
+            min.into_iter().chain(max).map(|(ident, value)| quote! { pub const #ident: usize = #value; })
+        });
+        let channel_type_constants = input.options.iter().filter(|x| x.has_channel_types()).map(|opt| {
+            let ident = channel_types_constant(opt);
+            let channel_types = opt.channel_types.iter().map(|&x| channel_type(x));
+            quote! { pub const #ident: &'static [ChannelType] = &[#(#channel_types),*]; }
+        });
+        quote! {
+            impl Options {
+                #(#length_constants)*
+                #(#channel_type_constants)*
+            }
+        }
+    }))
+}
+
+// the accessors are only generated alongside our own `Resolved`, since a user-provided one might be shaped differently
+fn generate_resolved_accessors<'a>(
+    input: &'a CommandOption,
+    resolved_struct: Option<&'a str>,
+) -> impl ToTokens + 'a {
+    let has_accessors = input.options.iter().any(|x| !x.resolved_entities().is_empty());
+    Defer((resolved_struct.is_none() && has_accessors, move || {
+        let accessors = input.options.iter().flat_map(|opt| {
+            opt.resolved_entities().iter().map(move |entity| {
+                Defer(move || {
+                    let ident = opt.name.snake();
+                    let method = proc_macro2::Ident::new(
+                        &format!("{}_{}", ident, entity.suffix),
+                        proc_macro2::Span::call_site(),
+                    );
+                    let map = Defer(entity.map);
+                    let kind = Defer(entity.kind);
+                    let conversion = entity.conversion.map(|x| {
+                        let x = Defer(x);
+                        quote! { .#x() }
+                    });
+                    let lookup = if opt.required {
+                        quote! { resolved.#map.get(&self.#ident #conversion) }
+                    } else {
+                        quote! { self.#ident.and_then(|id| resolved.#map.get(&id #conversion)) }
+                    };
+                    quote! {
+                        pub fn #method<'a>(&self, resolved: &'a Resolved) -> Option<&'a #kind> {
+                            #lookup
+                        }
+                    }
+                })
+            })
+        });
+        quote! {
+            impl Options {
+                #(#accessors)*
+            }
+        }
+    }))
+}
+
+// `depth` is the number of modules between the generated options module and the top level of the generated code
+fn structify_data<'a>(
+    input: &'a CommandOption,
+    depth: usize,
+    resolved_struct: Option<&'a str>,
+) -> impl ToTokens + 'a {
+    Defer(move || {
+        let accessors = generate_resolved_accessors(input, resolved_struct);
+        let mut shared_types = input
+            .options
+            .iter()
+            .filter(|x| x.is_shared_type())
+            .map(|x| x.as_type().0)
+            .collect::<BTreeSet<_>>();
+        let entities = input.options.iter().flat_map(|x| x.resolved_entities());
+        if resolved_struct.is_none() && entities.clone().next().is_some() {
+            shared_types.insert("Resolved");
+            shared_types.extend(entities.map(|x| x.kind));
+        }
+        if input.options.iter().any(|x| x.has_channel_types()) {
+            shared_types.insert("ChannelType");
+        }
+        shared_types.insert("Locale");
+        let shared_import = Defer((!shared_types.is_empty(), || {
+            let supers = std::iter::repeat_n(Defer("super"), depth);
+            let shared_types = shared_types.iter().map(|&x| Defer(x));
+            quote! { use #(#supers)::*::{#(#shared_types),*}; }
+        }));
+        let kinds = input.options.iter().map(|x| x.as_field_type());
         let names = input.options.iter().map(|x| x.name.snake());
+        let docs = input.options.iter().map(|x| x.doc());
         let mod_ident = input.name.snake();
+        let mod_doc = input.doc();
 
         let kinds2 = input.options.iter().map(|opt| opt.as_type());
         let idents = input.options.iter().map(|opt| opt.name.snake());
+        let renames = input.options.iter().map(|opt| opt.name.serde_rename());
         let idents2 = input.options.iter().map(|opt| opt.name.snake());
+        let kinds3 = input.options.iter().map(|opt| opt.as_type());
+        let idents3 = input.options.iter().map(|opt| opt.name.snake());
+        let choice_enums = input.options.iter().filter(|x| !x.is_shared_enum()).map(generate_choice_enum);
+        let checks = input.options.iter().map(generate_checks);
+        let constants = generate_constants(input);
+        let channel_type_checks = generate_channel_type_checks(input, resolved_struct);
+        let partial_options = generate_partial_options(input);
+        let localized_fns = generate_localized_fns(input, None);
+        let option_localized_fns = input.options.iter().map(|x| generate_localized_fns(x, Some(x.name.snake().0)));
+        let fields = input.options.iter().map(|opt| {
+            Defer(move || {
+                let ident = opt.name.snake();
+                if opt.required {
+                    let name = opt.name.wire();
+                    quote! { #ident: partial.#ident.ok_or_else(|| <A::Error as serde::de::Error>::missing_field(#name))? }
+                } else {
+                    quote! { #ident: partial.#ident }
+                }
+            })
+        });
 
         quote! {
+            #mod_doc
+            // a command without subcommands gets a module of the same name inside its own
+            #[allow(clippy::module_inception)]
             pub mod #mod_ident {
                 use serde::{de::{SeqAccess, Visitor}, Deserializer};
                 use std::fmt;
+                #shared_import
 
-                #[derive(serde::Serialize, Debug, Default)]
+                #(#choice_enums)*
+
+                #[derive(serde::Serialize, Debug)]
                 pub struct Options {
-                    #(pub #names: #kinds,)*
+                    #(#docs pub #names: #kinds,)*
                 }
                 impl<'de> serde::Deserialize<'de> for Options {
                     fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Options, D::Error> {
@@ -112,65 +1156,107 @@ fn structify_data(input: &CommandOption) -> impl ToTokens + '_ {
                                 #[derive(serde::Deserialize, Debug)]
                                 #[serde(tag = "name", content = "value")]
                                 enum Property {
-                                    #(#idents(#kinds2),)*
+                                    #(#renames #idents(#kinds2),)*
+                                }
+
+                                // every option starts out missing; required ones are checked once the whole list is read
+                                #[derive(Default)]
+                                struct Partial {
+                                    #(#idents3: Option<#kinds3>,)*
                                 }
 
-                                let mut prop = Options::default();
+                                let mut partial = Partial::default();
                                 while let Some(tmp) = seq.next_element::<Property>()? {
                                     match tmp {
-                                        #(Property::#idents2(v) => prop.#idents2 = v,)*
+                                        #(Property::#idents2(v) => {
+                                            #checks
+                                            partial.#idents2 = Some(v);
+                                        })*
                                     }
                                 }
-                                Ok(prop)
+                                Ok(Options {
+                                    #(#fields,)*
+                                })
                             }
                         }
                         deserializer.deserialize_seq(PropertyParser)
                     }
                 }
+
+                #constants
+
+                #accessors
+
+                #channel_type_checks
+
+                #partial_options
+
+                impl Options {
+                    #localized_fns
+                    #(#option_localized_fns)*
+                }
             }
         }
     })
 }
 
+// splits the subcommands of a command into the ones at the root and the ones in each group; a command without any
+// subcommands is its own single root entry, and groups without any subcommands are treated like subcommands
 fn extract_modules(
     schema: &CommandOption,
-) -> (Vec<&CommandOption>, Vec<(&Name, Vec<&CommandOption>)>) {
-    fn recurse<'schema>(
-        next: &'schema CommandOption,
-        path: &mut Vec<&'schema Name>,
-        root: &mut Vec<&'schema CommandOption>,
-        modules: &mut Vec<(&'schema Name, Vec<&'schema CommandOption>)>,
-    ) {
-        if !next.options.is_empty() {
-            if next.options.iter().all(|x| x.options.is_empty()) {
-                if let Some(x) = path.get(1) {
-                    // should be correct as long as the traversal groups names together
-                    if !modules.is_empty() && &modules.last().unwrap().0 == x {
-                        modules.last_mut().unwrap().1.push(next);
-                    } else {
-                        modules.push((x, vec![next]));
-                    }
-                } else {
-                    root.push(next);
-                }
-            }
-            path.push(&next.name);
-            for i in &next.options {
-                recurse(i, path, root, modules);
+) -> (Vec<&CommandOption>, Vec<(&CommandOption, Vec<&CommandOption>)>) {
+    let mut root = Vec::new();
+    let mut modules = Vec::new();
+    if !schema.options.iter().any(|x| matches!(x.r#type, Some(Type::Subcommand | Type::SubcommandGroup))) {
+        if !schema.options.is_empty() {
+            root.push(schema);
+        }
+    } else {
+        for x in &schema.options {
+            match x.r#type {
+                Some(Type::SubcommandGroup) if !x.options.is_empty() => modules.push((x, x.options.iter().collect())),
+                _ => root.push(x),
             }
-            path.pop();
         }
     }
-    let mut root = Vec::new();
-    let mut modules = Vec::new();
-    recurse(schema, &mut Vec::new(), &mut root, &mut modules);
     (root, modules)
 }
 
-fn generate_interaction_struct<'a>(commands: &'a [CommandOption]) -> impl ToTokens + 'a {
+fn generate_interaction_struct<'a>(commands: &'a [Command]) -> impl ToTokens + 'a {
     Defer(move || {
-        let camels = commands.iter().map(|x| x.name.camel());
-        let snakes = commands.iter().map(|x| x.name.snake());
+        let camels = commands.iter().map(|x| x.name().camel());
+        let snakes = commands.iter().map(|x| x.name().snake());
+        let docs = commands.iter().map(|x| x.doc());
+        let command_dispatch = generate_command_dispatch(
+            Defer("Command"),
+            commands.iter().map(|x| {
+                let (snake, camel) = (x.name().snake(), x.name().camel());
+                (x.name(), quote! { #snake::#camel })
+            }),
+        );
+        let autocomplete_commands = commands.iter().filter(|x| x.has_autocomplete());
+        let autocomplete_camels = autocomplete_commands.clone().map(|x| x.name().camel());
+        let autocomplete_docs = autocomplete_commands.clone().map(|x| x.doc());
+        let autocomplete_dispatch = generate_command_dispatch(
+            Defer("AutocompleteCommand"),
+            autocomplete_commands.clone().map(|x| {
+                let (snake, ident) = (x.name().snake(), autocomplete_ident(x.name()));
+                (x.name(), quote! { #snake::#ident })
+            }),
+        );
+        let autocomplete_types = autocomplete_commands.map(|x| {
+            let (snake, ident) = (x.name().snake(), autocomplete_ident(x.name()));
+            quote! { #snake::#ident }
+        });
+        let id_types = ["UserId", "ChannelId", "RoleId", "MentionableId", "AttachmentId", "MessageId"].map(Defer);
+        let channel_types = CHANNEL_TYPES.iter().map(|&(_, x)| Defer(x)).collect::<Vec<_>>();
+        let channel_type_values = CHANNEL_TYPES.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+        let contexts = CONTEXTS.iter().map(|&(_, x)| Defer(x)).collect::<Vec<_>>();
+        let context_values = CONTEXTS.iter().map(|&(x, _)| x).collect::<Vec<_>>();
+        let permissions = PERMISSIONS.iter().map(|&(_, x)| Defer(x));
+        let permission_bits = PERMISSIONS.iter().map(|&(x, _)| 1_u64 << x);
+        let locale_codes = LOCALES.iter().map(|&(x, _)| x);
+        let locale_variants = LOCALES.iter().map(|&(_, x)| Defer(x));
         quote! {
             #[derive(serde::Serialize, Debug)]
             #[serde(tag = "type")]
@@ -178,6 +1264,7 @@ fn generate_interaction_struct<'a>(commands: &'a [CommandOption]) -> impl ToToke
             pub enum Interaction {
                 Ping(Ping),
                 ApplicationCommand(ApplicationCommand),
+                Autocomplete(Autocomplete),
             }
             use serde::de::Error;
             // the issue which would let me do this via derive is 4 years old https://github.com/serde-rs/serde/issues/745 </3
@@ -201,7 +1288,13 @@ fn generate_interaction_struct<'a>(commands: &'a [CommandOption]) -> impl ToToke
                                     D::Error::custom(x.to_string())
                                 })?,
                             ),
-                            _ => panic!("type isn't valid"),
+                            4 =>
+                            Interaction::Autocomplete(
+                                Autocomplete::deserialize(value).map_err(|x| {
+                                    D::Error::custom(x.to_string())
+                                })?,
+                            ),
+                            x => return Err(D::Error::custom(format!("{} isn't a supported interaction type", x))),
                         },
                     )
                 }
@@ -218,27 +1311,54 @@ fn generate_interaction_struct<'a>(commands: &'a [CommandOption]) -> impl ToToke
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
             pub struct ApplicationCommand {
                 pub application_id: String,
-                pub channel_id: String,
+                pub channel_id: ChannelId,
                 pub data: Command,
                 pub guild_id: Option<String>, // not present in DMs
                 pub id: String,
                 pub member: Option<PartialMember>,
                 pub user: Option<User>,
+                pub context: Option<InteractionContextType>,
                 pub token: String,
                 pub r#type: u64,
                 pub version: u64,
             }
 
-            #[derive(serde::Serialize, serde::Deserialize, Debug)]
+            #[derive(serde::Serialize, Debug)]
             #[serde(untagged)]
             pub enum Command {
-                #(#camels(#snakes::#camels),)*
+                #(#docs #camels(#snakes::#camels),)*
                 Other { id: String, name: String }
             }
 
+            #command_dispatch
+
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
-            pub struct User {
+            pub struct Autocomplete {
+                pub application_id: String,
+                pub channel_id: ChannelId,
+                pub data: AutocompleteCommand,
+                pub guild_id: Option<String>, // not present in DMs
                 pub id: String,
+                pub member: Option<PartialMember>,
+                pub user: Option<User>,
+                pub context: Option<InteractionContextType>,
+                pub token: String,
+                pub r#type: u64,
+                pub version: u64,
+            }
+
+            #[derive(serde::Serialize, Debug)]
+            #[serde(untagged)]
+            pub enum AutocompleteCommand {
+                #(#autocomplete_docs #autocomplete_camels(#autocomplete_types),)*
+                Other { id: String, name: String }
+            }
+
+            #autocomplete_dispatch
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug)]
+            pub struct User {
+                pub id: UserId,
                 pub username: String,
                 pub discriminator: String,
                 pub avatar: String,
@@ -253,17 +1373,178 @@ fn generate_interaction_struct<'a>(commands: &'a [CommandOption]) -> impl ToToke
                 pub public_flags: Option<u64>,
             }
 
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+            pub struct Snowflake(pub u64);
+
+            impl std::fmt::Display for Snowflake {
+                fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl serde::Serialize for Snowflake {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(self)
+                }
+            }
+
+            // snowflakes are sent as strings since they don't fit in a javascript number
+            impl<'de> serde::Deserialize<'de> for Snowflake {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Snowflake, D::Error> {
+                    struct SnowflakeVisitor;
+                    impl<'de> serde::de::Visitor<'de> for SnowflakeVisitor {
+                        type Value = Snowflake;
+                        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                            f.write_str("a snowflake as a string of digits")
+                        }
+                        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Snowflake, E> {
+                            v.parse().map(Snowflake).map_err(|_| E::invalid_value(serde::de::Unexpected::Str(v), &self))
+                        }
+                        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Snowflake, E> {
+                            Ok(Snowflake(v))
+                        }
+                    }
+                    deserializer.deserialize_any(SnowflakeVisitor)
+                }
+            }
+
+            #(
+                #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+                #[serde(transparent)]
+                pub struct #id_types(pub Snowflake);
+
+                impl std::fmt::Display for #id_types {
+                    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                        self.0.fmt(f)
+                    }
+                }
+            )*
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[serde(from = "u64", into = "u64")]
+            pub enum ChannelType {
+                #(#channel_types,)*
+                Unknown(u64),
+            }
+
+            impl From<u64> for ChannelType {
+                fn from(x: u64) -> ChannelType {
+                    match x {
+                        #(#channel_type_values => ChannelType::#channel_types,)*
+                        x => ChannelType::Unknown(x),
+                    }
+                }
+            }
+
+            impl From<ChannelType> for u64 {
+                fn from(x: ChannelType) -> u64 {
+                    match x {
+                        #(ChannelType::#channel_types => #channel_type_values,)*
+                        ChannelType::Unknown(x) => x,
+                    }
+                }
+            }
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[serde(from = "u64", into = "u64")]
+            pub enum InteractionContextType {
+                #(#contexts,)*
+                Unknown(u64),
+            }
+
+            impl From<u64> for InteractionContextType {
+                fn from(x: u64) -> InteractionContextType {
+                    match x {
+                        #(#context_values => InteractionContextType::#contexts,)*
+                        x => InteractionContextType::Unknown(x),
+                    }
+                }
+            }
+
+            impl From<InteractionContextType> for u64 {
+                fn from(x: InteractionContextType) -> u64 {
+                    match x {
+                        #(InteractionContextType::#contexts => #context_values,)*
+                        InteractionContextType::Unknown(x) => x,
+                    }
+                }
+            }
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+            pub struct Permissions(pub u64);
+
+            impl Permissions {
+                #(pub const #permissions: Permissions = Permissions(#permission_bits);)*
+
+                pub const fn empty() -> Permissions {
+                    Permissions(0)
+                }
+
+                pub const fn union(self, other: Permissions) -> Permissions {
+                    Permissions(self.0 | other.0)
+                }
+
+                pub const fn contains(self, other: Permissions) -> bool {
+                    self.0 & other.0 == other.0
+                }
+            }
+
+            impl std::ops::BitOr for Permissions {
+                type Output = Permissions;
+                fn bitor(self, other: Permissions) -> Permissions {
+                    self.union(other)
+                }
+            }
+
+            impl std::ops::BitAnd for Permissions {
+                type Output = Permissions;
+                fn bitand(self, other: Permissions) -> Permissions {
+                    Permissions(self.0 & other.0)
+                }
+            }
+
+            impl serde::Serialize for Permissions {
+                fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    serializer.collect_str(&self.0)
+                }
+            }
+
+            // permissions are sent as strings for the same reason as snowflakes
+            impl<'de> serde::Deserialize<'de> for Permissions {
+                fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Permissions, D::Error> {
+                    Snowflake::deserialize(deserializer).map(|x| Permissions(x.0))
+                }
+            }
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum Locale {
+                #(
+                    #[serde(rename = #locale_codes)]
+                    #locale_variants,
+                )*
+            }
+
+            // a mentionable option is either a user or a role, so look it up in whichever map it's in
+            impl MentionableId {
+                pub fn as_user(self) -> UserId {
+                    UserId(self.0)
+                }
+                pub fn as_role(self) -> RoleId {
+                    RoleId(self.0)
+                }
+            }
+
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
             pub struct PartialMember {
                 pub user: Option<User>,
                 pub nick: Option<String>,
-                pub roles: Vec<String>,
+                pub roles: Vec<RoleId>,
                 pub joined_at: String,
                 pub premium_since: Option<String>,
                 pub deaf: Option<bool>,
                 pub mute: Option<bool>,
                 pub pending: Option<bool>,
-                pub permissions: Option<String>,
+                pub permissions: Option<Permissions>,
             }
 
         }
@@ -278,18 +1559,22 @@ fn generate_resolved_structs(resolved_struct: Option<&str>) -> impl ToTokens {
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
             pub struct Resolved {
                 #[serde(default)]
-                pub users: HashMap<String, User>,
+                pub users: HashMap<UserId, User>,
+                #[serde(default)]
+                pub members: HashMap<UserId, PartialMember>,
                 #[serde(default)]
-                pub members: HashMap<String, PartialMember>,
+                pub roles: HashMap<RoleId, Role>,
                 #[serde(default)]
-                pub roles: HashMap<String, Role>,
+                pub channels: HashMap<ChannelId, PartialChannel>,
                 #[serde(default)]
-                pub channels: HashMap<String, PartialChannel>,
+                pub attachments: HashMap<AttachmentId, Attachment>,
+                #[serde(default)]
+                pub messages: HashMap<MessageId, Message>,
             }
 
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
             pub struct Role {
-                pub id: String,
+                pub id: RoleId,
                 pub name: String,
                 pub color: u64,
                 pub hoist: bool,
@@ -308,9 +1593,43 @@ fn generate_resolved_structs(resolved_struct: Option<&str>) -> impl ToTokens {
             }
 
             #[derive(serde::Serialize, serde::Deserialize, Debug)]
-            pub struct PartialChannel {
-                pub id: String,
+            pub struct Attachment {
+                pub id: AttachmentId,
+                pub filename: String,
+                pub description: Option<String>,
+                pub content_type: Option<String>,
+                pub size: u64,
+                pub url: String,
+                pub proxy_url: String,
+                pub height: Option<u64>,
+                pub width: Option<u64>,
+                pub ephemeral: Option<bool>,
+            }
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug)]
+            pub struct Message {
+                pub id: MessageId,
+                pub channel_id: ChannelId,
+                pub author: User,
+                pub content: String,
+                pub timestamp: String,
+                pub edited_timestamp: Option<String>,
+                pub tts: bool,
+                pub mention_everyone: bool,
+                #[serde(default)]
+                pub mentions: Vec<User>,
+                #[serde(default)]
+                pub mention_roles: Vec<RoleId>,
+                #[serde(default)]
+                pub attachments: Vec<Attachment>,
+                pub pinned: bool,
                 pub r#type: u64,
+            }
+
+            #[derive(serde::Serialize, serde::Deserialize, Debug)]
+            pub struct PartialChannel {
+                pub id: ChannelId,
+                pub r#type: ChannelType,
                 pub name: String,
                 pub permissions: String
             }
@@ -319,30 +1638,191 @@ fn generate_resolved_structs(resolved_struct: Option<&str>) -> impl ToTokens {
     }))
 }
 
+/// the formats a schema can be written in; yaml and toml need the features of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    /// picks the format by file extension, falling back to json
+    pub fn from_path(path: impl AsRef<Path>) -> Format {
+        match path.as_ref().extension().and_then(|x| x.to_str()) {
+            Some("yaml" | "yml") => Format::Yaml,
+            Some("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+
+    fn parse(self, contents: &str) -> serde_json::Value {
+        match self {
+            Format::Json => serde_json::from_str(contents).unwrap(),
+            Format::Yaml => {
+                #[cfg(feature = "yaml")]
+                return serde_yaml::from_str(contents).unwrap();
+                #[cfg(not(feature = "yaml"))]
+                panic!("reading yaml schemas requires the `yaml` feature");
+            }
+            Format::Toml => {
+                #[cfg(feature = "toml")]
+                return toml::from_str(contents).unwrap();
+                #[cfg(not(feature = "toml"))]
+                panic!("reading toml schemas requires the `toml` feature");
+            }
+        }
+    }
+}
+
+/// the contents of a schema along with the format they're written in
+pub struct Schema {
+    format: Format,
+    contents: String,
+    // where the schema was read from, which `$ref`s to other files are relative to
+    path: Option<PathBuf>,
+}
+
+impl Schema {
+    pub fn new(format: Format, contents: impl Into<String>) -> Schema {
+        Schema { format, contents: contents.into(), path: None }
+    }
+
+    /// every other file the schema pulls in through `$ref`, which build scripts need to watch along with the schema
+    pub fn dependencies(&self) -> BTreeSet<PathBuf> {
+        fragment::resolve(self.format.parse(&self.contents), self.path.as_deref(), 0).1
+    }
+
+    /// reads a schema file, picking the format by its extension
+    pub fn read(path: impl AsRef<Path>) -> std::io::Result<Schema> {
+        let path = path.as_ref();
+        Ok(Schema { path: Some(path.to_path_buf()), ..Schema::new(Format::from_path(path), std::fs::read_to_string(path)?) })
+    }
+}
+
+/// plain strings are taken to be json
+impl<T: AsRef<str>> From<T> for Schema {
+    fn from(contents: T) -> Schema {
+        Schema::new(Format::Json, contents.as_ref())
+    }
+}
+
+// a schema holds either a single command or, like the body of the bulk overwrite endpoint, a list of them; `index`
+// is the schema's position in the input
+fn parse_schema(schema: &Schema, index: usize) -> Vec<Command> {
+    match fragment::resolve(schema.format.parse(&schema.contents), schema.path.as_deref(), index).0 {
+        serde_json::Value::Array(commands) => commands.into_iter().map(|x| serde_json::from_value(x).unwrap()).collect(),
+        command => vec![serde_json::from_value(command).unwrap()],
+    }
+}
+
 pub fn typify_driver(
-    input: impl IntoIterator<Item = impl AsRef<str>>,
+    input: impl IntoIterator<Item = impl Into<Schema>>,
     resolved_struct: Option<&str>,
 ) -> TokenStream {
     let schemas = input
         .into_iter()
-        .map(|schema| serde_json::from_str(schema.as_ref()).unwrap())
-        .collect::<Vec<CommandOption>>();
+        .enumerate()
+        .flat_map(|(i, schema)| parse_schema(&schema.into(), i))
+        .collect::<Vec<Command>>();
+    if let Err(violations) = validate::validate(&schemas) {
+        panic!("invalid schema:\n  {}", violations.join("\n  "));
+    }
 
-    let tokens = schemas
+    let tokens = schemas.iter().map(|x| {
+        Defer(move || match x {
+            Command::ChatInput(schema) => generate_command_data(schema, resolved_struct).into_token_stream(),
+            Command::User(menu) => generate_context_menu_data(menu, &USER, resolved_struct).into_token_stream(),
+            Command::Message(menu) => generate_context_menu_data(menu, &MESSAGE, resolved_struct).into_token_stream(),
+        })
+    });
+    let shared_enums = schemas
         .iter()
-        .map(|x| generate_command_data(x, resolved_struct));
+        .filter_map(|x| match x {
+            Command::ChatInput(schema) => Some(schema),
+            _ => None,
+        })
+        .flat_map(CommandOption::shared_enums)
+        .map(|x| (x.fragment.as_ref().map(|x| &x.id), x))
+        .collect::<BTreeMap<_, _>>();
+    let shared_enums = shared_enums.into_values().map(generate_choice_enum);
     let resolved_code = generate_resolved_structs(resolved_struct);
     let interaction_struct = generate_interaction_struct(&schemas);
 
     quote! {
         #(#tokens)*
 
+        #(#shared_enums)*
+
         #interaction_struct
 
         #resolved_code
     }
 }
 
+// `target` is the entry of `Resolved` which the command's `target_id` points into
+fn generate_context_menu_data<'a>(
+    menu: &'a ContextMenu,
+    target: &'a ResolvedEntity,
+    resolved_struct: Option<&'a str>,
+) -> impl ToTokens + 'a {
+    Defer(move || {
+        let mod_ident = menu.name.snake();
+        let struct_ident = menu.name.camel();
+        let target_id = Defer(target.id);
+        let localized_name = localized_match(menu.name.wire(), &menu.name_localizations);
+        let metadata_constants = generate_metadata_constants(&menu.metadata);
+        let resolved_type = Defer(move || {
+            if let Some(name) = resolved_struct {
+                let ident = Defer(name);
+                quote! { #ident }
+            } else {
+                quote! { super::Resolved }
+            }
+        });
+        let accessors = Defer((resolved_struct.is_none(), || {
+            let entities = match target.map {
+                "users" => vec![&USER, &MEMBER],
+                _ => vec![target],
+            };
+            let accessors = entities.into_iter().map(|entity| {
+                let method = proc_macro2::Ident::new(&format!("target_{}", entity.suffix), proc_macro2::Span::call_site());
+                let map = Defer(entity.map);
+                let kind = Defer(entity.kind);
+                quote! {
+                    pub fn #method(&self) -> Option<&super::#kind> {
+                        self.resolved.as_ref()?.#map.get(&self.target_id)
+                    }
+                }
+            });
+            quote! { #(#accessors)* }
+        }));
+        quote! {
+            pub mod #mod_ident {
+                use super::{Locale, #target_id};
+
+                #[derive(serde::Serialize, serde::Deserialize, Debug)]
+                pub struct #struct_ident {
+                    pub id: String,
+                    pub target_id: #target_id,
+                    pub resolved: Option<#resolved_type>,
+                }
+
+                impl #struct_ident {
+                    #metadata_constants
+
+                    #[allow(unreachable_patterns)]
+                    pub const fn localized_name(locale: Locale) -> &'static str {
+                        #localized_name
+                    }
+
+                    #accessors
+                }
+            }
+        }
+    })
+}
+
 fn generate_command_data<'a>(
     schema: &'a CommandOption,
     resolved_struct: Option<&'a str>,
@@ -352,36 +1832,83 @@ fn generate_command_data<'a>(
 
             let root_name_camelcase = schema.name.camel();
             let root_name = schema.name.snake();
+            let root_doc = schema.doc();
+            let root_localized_fns = generate_localized_fns(schema, None);
+            let root_metadata_constants = generate_metadata_constants(&schema.metadata);
+            let root_rename = schema.name.serde_rename();
             let subcommand_struct_tokens = modules.iter().map(|(k, v)| {
                 Defer(move || {
-                    let mod_ident = k.snake();
-                    let enum_ident = k.camel();
+                    let mod_ident = k.name.snake();
+                    let enum_ident = k.name.camel();
+                    let doc = k.doc();
+                    let localized_fns = generate_localized_fns(k, None);
+                    let unit_localized_fns = generate_unit_localized_fns(v);
                     let fields = v
                         .iter()
-                        .map(|x| (!x.options.is_empty()).then(|| structify_data(x)));
-                    let type_idents = v.iter().map(|x| x.name.snake());
-                    let type_idents_camelcase = v.iter().map(|x| x.name.camel());
+                        .map(|x| (!x.options.is_empty()).then(|| structify_data(x, 3, resolved_struct)));
+                    let variants = v.iter().map(|x| generate_options_variant(x, "super::parse_empty"));
+                    let check_dispatch = generate_check_dispatch(
+                        enum_ident,
+                        v.iter().map(|x| (x.restricts_channels(), x.name.camel())),
+                        quote! { super::super::Resolved },
+                        resolved_struct,
+                    );
+                    let autocomplete_enum = Defer((v.iter().any(|x| x.has_autocomplete()), || {
+                        let autocomplete_enum_ident = autocomplete_ident(&k.name);
+                        let autocomplete = v.iter().filter(|x| x.has_autocomplete());
+                        let snakes = autocomplete.clone().map(|x| x.name.snake());
+                        let docs = autocomplete.clone().map(|x| x.doc());
+                        let renames = autocomplete.clone().map(|x| x.name.serde_rename());
+                        let camels = autocomplete.map(|x| x.name.camel());
+                        quote! {
+                            #doc
+                            #[derive(serde::Serialize, serde::Deserialize, Debug)]
+                            #[serde(tag = "name", content = "options")]
+                            pub enum #autocomplete_enum_ident {
+                                #(#docs #renames #camels(#snakes::PartialOptions),)*
+                            }
+                        }
+                    }));
                     quote! {
+                        #doc
                         pub mod #mod_ident {
+                            use super::super::Locale;
+
                             #(#fields)*
 
+                            #doc
                             #[derive(serde::Serialize, serde::Deserialize, Debug)]
                             #[serde(tag = "name", content = "options")]
-                            #[serde(rename_all = "snake_case")]
                             pub enum #enum_ident {
-                                #(#type_idents_camelcase(#type_idents::Options),)*
+                                #(#variants,)*
+                            }
+
+                            impl #enum_ident {
+                                #localized_fns
+
+                                #unit_localized_fns
                             }
 
+                            #check_dispatch
+
+                            #autocomplete_enum
                         }
                     }
                 })
             });
             let has_options = root.iter().any(|x| x.r#type.is_none());
+            // a command without any options at all, which discord sends without an `options` field
+            let is_empty = root.is_empty() && modules.is_empty();
             let options_type_tokens = Defer(|| {
                 if has_options {
                     let x = root.first().expect("root to be nonempty");
                     let x_ident = x.name.snake();
                     quote! { pub options: #x_ident::Options }
+                } else if is_empty {
+                    quote! {
+                        #[serde(default, deserialize_with = "parse_empty")]
+                        pub options: ()
+                    }
                 } else {
                     quote! {
                         #[serde(deserialize_with = "parse_single")]
@@ -389,21 +1916,79 @@ fn generate_command_data<'a>(
                     }
                 }
             });
-            let options_enum_tokens = Defer((!has_options, || {
-                let root_enum_snake = root.iter().map(|x| x.name.snake());
-                let root_enum_camel = root.iter().map(|x| x.name.camel());
-                let root_module_snake = modules.iter().map(|(x, _)| x.snake());
-                let root_module_camel = modules.iter().map(|(x, _)| x.camel());
+            let parse_empty = Defer((
+                is_empty || root.iter().chain(modules.iter().flat_map(|(_, v)| v)).any(|x| x.options.is_empty()),
+                || {
+                    quote! {
+                        fn parse_empty<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
+                            struct EmptyParser;
+                            impl<'de> serde::de::Visitor<'de> for EmptyParser {
+                                type Value = ();
+                                fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                                    formatter.write_str("no options")
+                                }
+
+                                fn visit_none<E: serde::de::Error>(self) -> Result<(), E> {
+                                    Ok(())
+                                }
+
+                                fn visit_unit<E: serde::de::Error>(self) -> Result<(), E> {
+                                    Ok(())
+                                }
+
+                                fn visit_some<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+                                    deserializer.deserialize_any(self)
+                                }
+
+                                fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+                                    match seq.next_element::<serde::de::IgnoredAny>()? {
+                                        Some(_) => Err(<A::Error as serde::de::Error>::invalid_length(1, &self)),
+                                        None => Ok(()),
+                                    }
+                                }
+                            }
+                            deserializer.deserialize_option(EmptyParser)
+                        }
+                    }
+                },
+            ));
+            let options_enum_tokens = Defer((!has_options && !is_empty, || {
+                let root_variants = root.iter().map(|x| generate_options_variant(x, "parse_empty"));
+                let unit_localized_fns = generate_unit_localized_fns(&root);
+                let root_module_snake = modules.iter().map(|(x, _)| x.name.snake());
+                let root_module_camel = modules.iter().map(|(x, _)| x.name.camel());
+                let root_module_docs = modules.iter().map(|(x, _)| x.doc());
+                let root_module_renames = modules.iter().map(|(x, _)| x.name.serde_rename());
+                let check_dispatch = generate_check_dispatch(
+                    Defer("Options"),
+                    root.iter()
+                        .map(|x| (x.restricts_channels(), x.name.camel()))
+                        .chain(modules.iter().map(|(group, v)| {
+                            (v.iter().any(|x| x.restricts_channels()), group.name.camel())
+                        })),
+                    quote! { super::Resolved },
+                    resolved_struct,
+                );
                 // this deserializer relies on the assumption that there can only be a single subcommand active at a time
                 quote! {
                     #[derive(serde::Serialize, serde::Deserialize, Debug)]
-                    #[serde(tag = "name", content = "options", rename_all = "snake_case")]
+                    #[serde(tag = "name", content = "options")]
                     pub enum Options {
-                        #(#root_enum_camel(#root_enum_snake::Options),)*
-                        #[serde(deserialize_with = "parse_single")]
-                        #(#root_module_camel(#root_module_snake::#root_module_camel),)*
+                        #(#root_variants,)*
+                        #(
+                            #root_module_docs
+                            #root_module_renames
+                            #[serde(deserialize_with = "parse_single")]
+                            #root_module_camel(#root_module_snake::#root_module_camel),
+                        )*
                     }
 
+                    impl Options {
+                        #unit_localized_fns
+                    }
+
+                    #check_dispatch
+
                     use serde::{de::{SeqAccess, Visitor, Error}, Deserializer};
                     use std::fmt;
                     use std::marker::PhantomData;
@@ -423,6 +2008,7 @@ fn generate_command_data<'a>(
                         }
                         deserializer.deserialize_seq(PropertyParser(PhantomData))
                     }
+
                 }
             }));
             let resolved_type = Defer(move || {
@@ -435,22 +2021,120 @@ fn generate_command_data<'a>(
             });
             let root_struct_tokens = root
                 .iter()
-                .map(|x| (!x.options.is_empty()).then(|| structify_data(x)));
+                .map(|x| (!x.options.is_empty()).then(|| structify_data(x, 2, resolved_struct)));
+            let checks_resolved = resolved_struct.is_none() && schema.restricts_channels();
+            let try_from_attr = Defer((checks_resolved, || quote! { #[serde(try_from = "Unchecked")] }));
+            let unchecked_tokens = Defer((checks_resolved, || {
+                quote! {
+                    #[derive(serde::Deserialize)]
+                    #[serde(tag = "name")]
+                    struct Unchecked {
+                        pub id: String,
+                        #options_type_tokens,
+                        pub resolved: Option<#resolved_type>,
+                    }
+
+                    impl std::convert::TryFrom<Unchecked> for #root_name_camelcase {
+                        type Error = String;
+                        fn try_from(unchecked: Unchecked) -> Result<#root_name_camelcase, String> {
+                            if let Some(resolved) = &unchecked.resolved {
+                                unchecked.options.check_resolved(resolved)?;
+                            }
+                            Ok(#root_name_camelcase {
+                                id: unchecked.id,
+                                options: unchecked.options,
+                                resolved: unchecked.resolved,
+                            })
+                        }
+                    }
+                }
+            }));
+            let autocomplete_tokens = Defer((schema.has_autocomplete(), || {
+                let autocomplete_struct_ident = autocomplete_ident(&schema.name);
+                if has_options {
+                    let x_ident = schema.name.snake();
+                    quote! {
+                        #root_doc
+                        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+                        #[serde(tag = "name")]
+                        #root_rename
+                        pub struct #autocomplete_struct_ident {
+                            pub id: String,
+                            pub options: #x_ident::PartialOptions,
+                            pub resolved: Option<#resolved_type>,
+                        }
+                    }
+                } else {
+                    let root_autocomplete = root.iter().filter(|x| x.has_autocomplete());
+                    let root_snake = root_autocomplete.clone().map(|x| x.name.snake());
+                    let root_docs = root_autocomplete.clone().map(|x| x.doc());
+                    let root_renames = root_autocomplete.clone().map(|x| x.name.serde_rename());
+                    let root_camel = root_autocomplete.map(|x| x.name.camel());
+                    let module_autocomplete = modules.iter().filter(|(_, v)| v.iter().any(|x| x.has_autocomplete()));
+                    let module_snake = module_autocomplete.clone().map(|(x, _)| x.name.snake());
+                    let module_camel = module_autocomplete.clone().map(|(x, _)| x.name.camel());
+                    let module_docs = module_autocomplete.clone().map(|(x, _)| x.doc());
+                    let module_renames = module_autocomplete.clone().map(|(x, _)| x.name.serde_rename());
+                    let module_idents = module_autocomplete.map(|(x, _)| autocomplete_ident(&x.name));
+                    quote! {
+                        #root_doc
+                        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+                        #[serde(tag = "name")]
+                        #root_rename
+                        pub struct #autocomplete_struct_ident {
+                            pub id: String,
+                            #[serde(deserialize_with = "parse_single")]
+                            pub options: AutocompleteOptions,
+                            pub resolved: Option<#resolved_type>,
+                        }
+
+                        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+                        #[serde(tag = "name", content = "options")]
+                        pub enum AutocompleteOptions {
+                            #(#root_docs #root_renames #root_camel(#root_snake::PartialOptions),)*
+                            #(
+                                #module_docs
+                                #module_renames
+                                #[serde(deserialize_with = "parse_single")]
+                                #module_camel(#module_snake::#module_idents),
+                            )*
+                        }
+                    }
+                }
+            }));
             quote! {
+                #root_doc
                 pub mod #root_name {
+                    use super::Locale;
+
                     #(#root_struct_tokens)*
 
+                    #autocomplete_tokens
+
+                    #root_doc
                     #[derive(serde::Serialize, serde::Deserialize, Debug)]
-                    #[serde(tag = "name", rename_all ="snake_case")] // undocumented functionality; see https://github.com/serde-rs/serde/issues/1684
+                    #[serde(tag = "name")] // undocumented functionality; see https://github.com/serde-rs/serde/issues/1684
+                    #root_rename
+                    #try_from_attr
                     pub struct #root_name_camelcase {
                         pub id: String,
                         #options_type_tokens,
                         pub resolved: Option<#resolved_type>,
                     }
 
+                    impl #root_name_camelcase {
+                        #root_metadata_constants
+
+                        #root_localized_fns
+                    }
+
+                    #unchecked_tokens
+
 
                     #options_enum_tokens
 
+                    #parse_empty
+
                     #(#subcommand_struct_tokens)*
                 }
 
@@ -460,7 +2144,9 @@ fn generate_command_data<'a>(
 
 #[cfg(test)]
 mod tests {
-    use crate::{extract_modules, CommandOption, Name, Type};
+    use crate::{extract_modules, parse_schema, validate, Command, CommandOption, Name, Schema, Type};
+    use std::collections::BTreeMap;
+    use quote::{quote, ToTokens};
     use serde_json::json;
 
     #[test]
@@ -474,12 +2160,433 @@ mod tests {
             x,
             CommandOption {
                 name: Name::new("abc").unwrap(),
-                r#type: Some(Type::U64),
+                description: String::new(),
+                name_localizations: BTreeMap::new(),
+                description_localizations: BTreeMap::new(),
+                r#type: Some(Type::Integer),
+                required: false,
+                choices: vec![],
+                min_value: None,
+                max_value: None,
+                min_length: None,
+                max_length: None,
+                channel_types: vec![],
+                autocomplete: false,
                 options: vec![],
+                metadata: Default::default(),
+                rust_name: None,
+                fragment: None,
             }
         );
     }
 
+    #[test]
+    fn generates_doc_attributes() {
+        let x: CommandOption = serde_json::from_value(json!({
+            "type": 3,
+            "name": "flag",
+            "description": "the flag you found"
+        }))
+        .unwrap();
+        assert_eq!(x.doc().into_token_stream().to_string(), quote! { #[doc = "the flag you found"] }.to_string());
+    }
+
+    #[test]
+    fn rejects_unknown_locales() {
+        let x = serde_json::from_value::<CommandOption>(json!({
+            "type": 3,
+            "name": "flag",
+            "name_localizations": { "fr": "drapeau", "xx": "flag" }
+        }));
+        assert!(x.is_err());
+    }
+
+    #[test]
+    fn deserializes_context_menus() {
+        let x: Command = serde_json::from_value(json!({ "type": 2, "name": "Report User" })).unwrap();
+        assert!(matches!(&x, Command::User(menu) if menu.name.snake().to_string() == "report_user"));
+        assert_eq!(x.name().wire(), "Report User");
+
+        let x: Command = serde_json::from_value(json!({ "type": 3, "name": "Quote" })).unwrap();
+        assert!(matches!(x, Command::Message(_)));
+
+        let x: Command = serde_json::from_value(json!({ "name": "ctf", "description": "manage ctfs" })).unwrap();
+        assert!(matches!(x, Command::ChatInput(_)));
+
+        assert!(serde_json::from_value::<Command>(json!({ "type": 4, "name": "ctf" })).is_err());
+    }
+
+    #[test]
+    fn deserializes_command_metadata() {
+        let x: CommandOption = serde_json::from_value(json!({
+            "name": "ctf",
+            "default_member_permissions": "40",
+            "dm_permission": false,
+            "contexts": [0],
+            "nsfw": true
+        }))
+        .unwrap();
+        assert_eq!(x.metadata.default_member_permissions, Some(40));
+        assert_eq!(x.metadata.dm_permission, Some(false));
+        assert_eq!(x.metadata.contexts, Some(vec![0]));
+        assert!(x.metadata.nsfw);
+
+        let x: CommandOption = serde_json::from_value(json!({ "name": "ctf", "default_member_permissions": null })).unwrap();
+        assert_eq!(x.metadata.default_member_permissions, None);
+
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "ctf", "contexts": [3] })).is_err());
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "ctf", "default_member_permissions": "admin" })).is_err());
+    }
+
+    #[test]
+    fn reports_every_limit_violation() {
+        let choices = (0..26).map(|x| json!({ "name": x.to_string(), "value": x })).collect::<Vec<_>>();
+        let x: Command = serde_json::from_value(json!({
+            "name": "ctf",
+            "description": "manage ctfs",
+            "options": [
+                { "type": 3, "name": "optional", "description": "" },
+                { "type": 4, "name": "required", "description": "placeholder", "required": true, "choices": choices },
+                { "type": 3, "name": "long", "description": "x".repeat(4000) }
+            ]
+        }))
+        .unwrap();
+        let violations = validate::validate(std::slice::from_ref(&x)).unwrap_err();
+        assert_eq!(violations.len(), 5, "{:#?}", violations);
+        assert!(violations[0].starts_with("ctf: required option `required` comes after an optional one"));
+        assert!(violations[1].starts_with("ctf > optional: description"));
+        assert!(violations[2].starts_with("ctf > required: has 26 choices"));
+        assert!(violations[3].starts_with("ctf > long: description"));
+        assert!(violations[4].starts_with("ctf: names, descriptions and choices add up to"));
+
+        let x: Command = serde_json::from_value(json!({ "name": "ctf", "description": "manage ctfs" })).unwrap();
+        assert_eq!(validate::validate(std::slice::from_ref(&x)), Ok(()));
+    }
+
+    #[test]
+    fn rejects_illegal_option_trees() {
+        let x: Command = serde_json::from_value(json!({
+            "name": "ctf",
+            "description": "manage ctfs",
+            "options": [
+                { "type": 1, "name": "play", "description": "placeholder" },
+                { "type": 3, "name": "name", "description": "placeholder" },
+                {
+                    "type": 2,
+                    "name": "players",
+                    "description": "placeholder",
+                    "options": [
+                        { "type": 6, "name": "add", "description": "placeholder" },
+                        {
+                            "type": 2,
+                            "name": "nested",
+                            "description": "placeholder",
+                            "options": [{ "type": 1, "name": "remove", "description": "placeholder" }]
+                        }
+                    ]
+                },
+                {
+                    "type": 1,
+                    "name": "solve",
+                    "description": "placeholder",
+                    "options": [
+                        { "type": 1, "name": "inner", "description": "placeholder" },
+                        {
+                            "type": 3,
+                            "name": "flag",
+                            "description": "placeholder",
+                            "options": [{ "type": 3, "name": "x", "description": "placeholder" }]
+                        }
+                    ]
+                }
+            ]
+        }))
+        .unwrap();
+        let violations = validate::validate(std::slice::from_ref(&x)).unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                "ctf > name: plain options cannot sit next to subcommands; move it into a subcommand",
+                "ctf > players > add: subcommand groups may only contain subcommands",
+                "ctf > players > nested: subcommand groups cannot be nested",
+                "ctf > solve > inner: subcommands may only contain plain options, not subcommands or groups",
+                "ctf > solve > flag: only subcommands and subcommand groups may have options",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_mistyped_values() {
+        let x: Command = serde_json::from_value(json!({
+            "name": "roll",
+            "description": "placeholder",
+            "options": [
+                {
+                    "type": 4,
+                    "name": "sides",
+                    "description": "placeholder",
+                    "choices": [{ "name": "six", "value": 6 }, { "name": "x", "value": "x" }, { "name": "half", "value": 0.5 }]
+                },
+                { "type": 5, "name": "loud", "description": "placeholder", "choices": [{ "name": "yes", "value": true }] },
+                { "type": 4, "name": "times", "description": "placeholder", "min_value": 1.5, "max_value": 10 },
+                { "type": 3, "name": "label", "description": "placeholder", "max_value": 10 },
+                { "type": 4, "name": "seed", "description": "placeholder", "min_length": 1 }
+            ]
+        }))
+        .unwrap();
+        let violations = validate::validate(std::slice::from_ref(&x)).unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                "roll > sides: choice `x` has the value \"x\", which isn't a valid INTEGER",
+                "roll > sides: choice `half` has the value 0.5, which isn't a valid INTEGER",
+                "roll > loud: choices are only allowed on STRING, INTEGER and NUMBER options, not BOOLEAN",
+                "roll > times: min_value is 1.5, which isn't a valid INTEGER",
+                "roll > label: max_value is only allowed on INTEGER and NUMBER options, not STRING",
+                "roll > seed: min_length is only allowed on STRING options, not INTEGER",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_focused_options_next_to_autocomplete() {
+        let x: Command = serde_json::from_value(json!({
+            "name": "search",
+            "description": "placeholder",
+            "options": [
+                { "type": 3, "name": "query", "description": "placeholder", "autocomplete": true },
+                { "type": 5, "name": "focused", "description": "placeholder" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            validate::validate(std::slice::from_ref(&x)).unwrap_err(),
+            ["search > focused: options of commands with autocomplete can't be called `focused`; set a `rust_name`"]
+        );
+
+        let x: Command = serde_json::from_value(json!({
+            "name": "search",
+            "description": "placeholder",
+            "options": [
+                { "type": 3, "name": "query", "description": "placeholder", "autocomplete": true },
+                { "type": 5, "name": "focused", "rust_name": "only_focused", "description": "placeholder" }
+            ]
+        }))
+        .unwrap();
+        validate::validate(std::slice::from_ref(&x)).unwrap();
+    }
+
+    #[test]
+    fn converts_unicode_names() {
+        let x = serde_json::from_value::<CommandOption>(json!({ "name": "プレイ-2" })).unwrap();
+        assert_eq!(x.name.snake().to_string(), "u30d7_u30ec_u30a4_2");
+        assert_eq!(x.name.camel().to_string(), "U30d7U30ecU30a42");
+        assert_eq!(x.name.wire(), "プレイ-2");
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "खेल" })).is_ok());
+        for name in ["★。", "名前!", "🎲", "x y"] {
+            let err = serde_json::from_value::<CommandOption>(json!({ "name": name })).unwrap_err();
+            assert!(err.to_string().contains("letters or digits from any script"), "{}", err);
+        }
+
+        let x: Command = serde_json::from_value(json!({
+            "name": "играть",
+            "rust_name": "play",
+            "options": [{ "type": 3, "name": "имя", "rust_name": "name" }]
+        }))
+        .unwrap();
+        let Command::ChatInput(x) = x else { panic!("expected a chat input command") };
+        assert_eq!(x.name.camel().to_string(), "Play");
+        assert_eq!(x.name.wire(), "играть");
+        assert_eq!(x.options[0].name.snake().to_string(), "name");
+
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "Играть" })).is_err());
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "play", "rust_name": "играть" })).is_err());
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "play", "name_localizations": { "ru": "Играть" } })).is_err());
+    }
+
+    #[test]
+    fn escapes_identifiers() {
+        let ident = |name: &str| {
+            let x = serde_json::from_value::<CommandOption>(json!({ "name": name })).unwrap();
+            (x.name.snake().into_token_stream().to_string(), x.name.camel().to_string())
+        };
+        assert_eq!(ident("type"), ("r#type".to_string(), "Type".to_string()));
+        assert_eq!(ident("self"), ("self_".to_string(), "Self_".to_string()));
+        assert_eq!(ident("1st"), ("_1st".to_string(), "_1st".to_string()));
+        assert!(serde_json::from_value::<CommandOption>(json!({ "name": "-_" })).is_err());
+    }
+
+    #[test]
+    fn reports_name_collisions() {
+        let x: Command = serde_json::from_value(json!({
+            "name": "ctf",
+            "description": "manage ctfs",
+            "options": [
+                { "type": 3, "name": "foo-bar", "description": "placeholder" },
+                { "type": 3, "name": "foo_bar", "description": "placeholder" },
+                { "type": 3, "name": "foo1", "description": "placeholder" },
+                { "type": 3, "name": "foo_1", "description": "placeholder" },
+                {
+                    "type": 3,
+                    "name": "options",
+                    "description": "placeholder",
+                    "choices": [{ "name": "Web", "value": "web" }, { "name": "web", "value": "web2" }]
+                }
+            ]
+        }))
+        .unwrap();
+        let violations = validate::validate(std::slice::from_ref(&x)).unwrap_err();
+        assert_eq!(
+            violations,
+            [
+                "ctf: `foo-bar` and `foo_bar` would both be called `foo_bar`; rename one of them or set a `rust_name`",
+                "ctf: `foo1` and `foo_1` would both be called `Foo1`; rename one of them or set a `rust_name`",
+                "ctf > options: `Web` and `web` would both be called `web`; rename one of them",
+                "ctf > options: the enum for its choices would be called `Options`, which is already taken; set a `rust_name`",
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_lists_of_commands() {
+        let commands = parse_schema(&r#"[{ "name": "ctf", "description": "manage ctfs" }, { "type": 3, "name": "Quote" }]"#.into(), 0);
+        assert_eq!(commands.iter().map(|x| x.name().wire()).collect::<Vec<_>>(), ["ctf", "Quote"]);
+        assert_eq!(parse_schema(&r#"{ "name": "ctf", "description": "manage ctfs" }"#.into(), 0).len(), 1);
+    }
+
+    #[test]
+    fn deserializes_exported_commands() {
+        let x: Command = serde_json::from_value(json!({
+            "id": "1107021540390633502",
+            "application_id": "867561056485769226",
+            "version": "1107021540390633503",
+            "type": 1,
+            "name": "roll",
+            "name_localizations": null,
+            "description": "rolls a die",
+            "default_permission": true,
+            "integration_types": [0]
+        }))
+        .unwrap();
+        let Command::ChatInput(x) = x else { panic!("expected a chat input command") };
+        assert_eq!(x.metadata.id, Some(1107021540390633502));
+        assert_eq!(x.metadata.application_id, Some(867561056485769226));
+        assert_eq!(x.metadata.version, Some(1107021540390633503));
+        assert_eq!(x.metadata.guild_id, None);
+    }
+
+    #[test]
+    fn resolves_fragments() {
+        let commands = parse_schema(
+            &r##"{
+                "name": "mod",
+                "description": "moderation",
+                "definitions": {
+                    "reason": { "type": 3, "name": "reason", "description": "why", "choices": [{ "name": "spam", "value": "spam" }] }
+                },
+                "options": [
+                    { "type": 1, "name": "ban", "description": "bans", "options": [{ "$ref": "#/definitions/reason", "required": true, "description": "why they're banned" }] },
+                    { "type": 1, "name": "kick", "description": "kicks", "options": [{ "$ref": "#/definitions/reason", "name": "why" }] }
+                ]
+            }"##
+            .into(),
+            0,
+        );
+        let Command::ChatInput(root) = &commands[0] else { panic!("expected a chat input command") };
+        let (ban, kick) = (&root.options[0].options[0], &root.options[1].options[0]);
+        assert_eq!(ban.name.wire(), "reason");
+        assert!(ban.required);
+        assert_eq!(ban.fragment.as_ref().map(|x| x.id.as_str()), Some("<schema 0>#/definitions/reason"));
+        // the shared enum is documented by the fragment, not by whichever `$ref` overrode the description
+        assert_eq!(ban.description, "why they're banned");
+        assert_eq!(ban.enum_doc().into_token_stream().to_string(), quote! { #[doc = "why"] }.to_string());
+        assert!(ban.is_shared_type());
+        // renaming it changes the enum's name, so it gets its own
+        assert_eq!(kick.name.wire(), "why");
+        assert_eq!(kick.fragment, None);
+        assert_eq!(root.shared_enums().len(), 1);
+    }
+
+    #[test]
+    fn lists_files_pulled_in_by_fragments() {
+        let schema = Schema::read("../test-harness/schema/moderate.json").unwrap();
+        let dependencies = schema.dependencies();
+        assert_eq!(dependencies.len(), 1);
+        assert!(dependencies.iter().all(|x| x.ends_with("fragments.json")));
+        assert!(Schema::read("../test-harness/schema/ctf.json").unwrap().dependencies().is_empty());
+    }
+
+    #[test]
+    fn keeps_fragments_of_different_schemas_apart() {
+        let schema = |name: &str, choice: &str| {
+            format!(
+                r##"{{
+                    "name": "{}",
+                    "description": "placeholder",
+                    "definitions": {{
+                        "r": {{ "type": 3, "name": "reason", "description": "why", "choices": [{{ "name": "{}", "value": "{}" }}] }}
+                    }},
+                    "options": [{{ "$ref": "#/definitions/r" }}]
+                }}"##,
+                name, choice, choice
+            )
+        };
+        let commands = [schema("a", "spam"), schema("b", "abuse")]
+            .iter()
+            .enumerate()
+            .flat_map(|(i, x)| parse_schema(&x.into(), i))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            validate::validate(&commands).unwrap_err(),
+            [
+                "<schema 0>#/definitions/r and <schema 1>#/definitions/r: the enums for their choices would both be called `Reason`; set a `rust_name` on one of them"
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`$fragment` is reserved")]
+    fn rejects_fragment_tags_in_schemas() {
+        parse_schema(
+            &r#"{ "name": "a", "description": "b", "options": [{ "type": 3, "name": "c", "$fragment": "x", "choices": [] }] }"#.into(),
+            0,
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`$ref` cycle")]
+    fn rejects_fragment_cycles() {
+        parse_schema(&r##"{ "name": "a", "description": "b", "options": [{ "$ref": "#/options/0" }] }"##.into(), 0);
+    }
+
+    #[test]
+    fn deserializes_number_option() {
+        let x: CommandOption = serde_json::from_value(json!({
+            "type": 10,
+            "name": "multiplier",
+            "required": true
+        }))
+        .unwrap();
+        assert_eq!(x.r#type, Some(Type::Number));
+        assert_eq!(x.as_type().to_string(), "f64");
+    }
+
+    #[test]
+    fn deserializes_choices() {
+        let x: CommandOption = serde_json::from_value(json!({
+            "type": 3,
+            "name": "category",
+            "choices": [
+                { "name": "Reverse Engineering", "value": "rev" },
+                { "name": "pwn", "value": "pwn" }
+            ]
+        }))
+        .unwrap();
+        assert_eq!(x.as_type().to_string(), "Category");
+        assert_eq!(x.choices[0].name.camel().to_string(), "ReverseEngineering");
+        assert_eq!(x.choices[1].name.camel().to_string(), "Pwn");
+    }
+
     #[test]
     fn extracts_modules() {
         let cmd_option = serde_json::from_str(include_str!(
@@ -488,4 +2595,21 @@ mod tests {
         .unwrap();
         let (_root, _submodules) = extract_modules(&cmd_option);
     }
+
+    #[test]
+    fn extracts_option_less_subcommands() {
+        let x: CommandOption = serde_json::from_value(json!({
+            "name": "ctf",
+            "options": [
+                { "type": 1, "name": "archive-all" },
+                { "type": 2, "name": "empty" },
+                { "type": 2, "name": "players", "options": [{ "type": 1, "name": "clear" }] }
+            ]
+        }))
+        .unwrap();
+        let (root, modules) = extract_modules(&x);
+        assert_eq!(root.iter().map(|x| x.name.wire()).collect::<Vec<_>>(), ["archive-all", "empty"]);
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0].1[0].name.wire(), "clear");
+    }
 }
@@ -1,111 +1,186 @@
 use crate::Defer;
-use std::ops::{Deref, DerefMut};
-use std::fmt::{Debug, Formatter, Display};
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens, TokenStreamExt};
+use std::fmt::{Formatter, Display};
 
 #[derive(Debug, Clone)]
 pub(crate) struct Name {
-    snake: Buffer,
-    camel: Buffer,
+    snake: String,
+    camel: String,
+    // the name exactly as it appears in the schema
+    wire: String,
 }
 
 impl Name {
-    // snake: replace all hyphens with underscores
+    // snake: replace all hyphens with underscores, and spell out every non-ascii character as its own word
     // camel: split on hyphens and underscores, uppercase first byte of each word
     pub(crate) fn new(s: &str) -> Option<Name> {
-        let bytes = validate(s)?;
-        let mut snake = Buffer::new();
-        snake.extend(bytes);
-        for b in snake.iter_mut() {
-            if *b == b'-' {
-                *b = b'_';
-            }
+        if !validate(s) {
+            return None;
         }
-        let mut camel = Buffer::new();
-        for word in bytes.split(|&b| b == b'_' || b == b'-') {
-            if let Some(&b) = word.first() {
-                camel.push(b.to_ascii_uppercase());
-                if let Some(bs) = word.get(1..) {
-                    camel.extend(bs);
+        let mut snake = String::new();
+        let mut after_code_point = false;
+        for c in s.chars() {
+            match c {
+                '-' | '_' => snake.push('_'),
+                c if c.is_ascii() => {
+                    if after_code_point {
+                        snake.push('_');
+                    }
+                    snake.push(c);
                 }
+                c => push_code_point(&mut snake, c),
+            }
+            after_code_point = !c.is_ascii();
+        }
+        Some(Name::from_snake(snake, s))
+    }
+
+    // replaces the generated identifiers with ones derived from `rust_name`, keeping the wire name
+    pub(crate) fn rename(&mut self, rust_name: &Name) {
+        self.snake = rust_name.snake.clone();
+        self.camel = rust_name.camel.clone();
+    }
+
+    // choice names are free-form text rather than command names, so every run of bytes outside of
+    // `a-zA-Z0-9` is treated as a word break and the rest is lowercased
+    pub(crate) fn choice(s: &str) -> Option<Name> {
+        Name::words(s, MAX_CHOICE_LEN)
+    }
+
+    // user and message command names may contain uppercase letters and spaces, e.g. `Report User`,
+    // and are split into words the same way as choice names
+    pub(crate) fn context_menu(s: &str) -> Option<Name> {
+        Name::words(s, MAX_LEN)
+    }
+
+    fn words(s: &str, max_len: usize) -> Option<Name> {
+        if s.is_empty() || s.chars().count() > max_len {
+            return None;
+        }
+        let mut snake = String::new();
+        for word in s.split(|c: char| !c.is_ascii_alphanumeric()).filter(|w| !w.is_empty()) {
+            if !snake.is_empty() {
+                snake.push('_');
+            }
+            snake.push_str(word);
+        }
+        // names without any ascii in them, e.g. in japanese, fall back to spelling out every code point
+        if snake.is_empty() {
+            for c in s.chars().filter(|c| c.is_alphanumeric()) {
+                push_code_point(&mut snake, c);
             }
         }
-        Some(Name { snake, camel })
+        if snake.is_empty() {
+            return None;
+        }
+        snake.make_ascii_lowercase();
+        Some(Name::from_snake(snake, s))
     }
-    pub(crate) fn snake(&self) -> Defer<&str> {
-        // SAFETY: `Name::new` ensures that all source bytes match `a-z0-9_-`, and
-        // all subsequent buffer writes use bytes that also match said pattern
-        unsafe { Defer(std::str::from_utf8_unchecked(&self.snake)) }
+
+    fn from_snake(snake: String, wire: &str) -> Name {
+        let mut camel = String::new();
+        for word in snake.split('_') {
+            let mut chars = word.chars();
+            if let Some(c) = chars.next() {
+                camel.push(c.to_ascii_uppercase());
+                camel.push_str(chars.as_str());
+            }
+        }
+        Name { snake: escape(snake), camel: escape(camel), wire: wire.to_string() }
+    }
+    pub(crate) fn wire(&self) -> &str {
+        &self.wire
+    }
+    // spelled out for every item rather than relying on `rename_all`, which can't get back to the wire name from
+    // the camel case form, e.g. for `add-player`
+    pub(crate) fn serde_rename(&self) -> impl ToTokens + '_ {
+        let wire = &self.wire;
+        quote! { #[serde(rename = #wire)] }
+    }
+    pub(crate) fn snake(&self) -> Ident<'_> {
+        Ident(&self.snake)
     }
     pub(crate) fn camel(&self) -> Defer<&str> {
-        // SAFETY: `Name::new` ensures that all source bytes match `a-z0-9_-`, and
-        // all subsequent buffer writes use bytes that also match said pattern
-        unsafe { Defer(std::str::from_utf8_unchecked(&self.camel)) }
+        Defer(&self.camel)
     }
 }
 
-fn validate(s: &str) -> Option<&[u8]> {
-    let bytes = s.as_bytes();
-    (1 <= bytes.len()
-        && bytes.len() <= MAX_LEN
-        && bytes
-        .iter()
-        .all(|b| matches!(b, b'a'..=b'z' | b'0'..=b'9' | b'_' | b'-')))
-        .then(|| bytes)
+// identifiers can't start with a digit, and the keywords which can't be used as raw identifiers get a trailing
+// underscore instead; the rest of the keywords are taken care of by `Ident`
+fn escape(mut ident: String) -> String {
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    if matches!(ident.as_str(), "self" | "Self" | "super" | "crate") {
+        ident.push('_');
+    }
+    ident
 }
 
-// NOTE: camel-case might be shorter by a few characters
-impl PartialEq for Name {
-    fn eq(&self, other: &Self) -> bool {
-        self.camel.deref() == other.camel.deref()
+// e.g. `u30d7` for `プ`, as a separate word from whatever comes before it
+fn push_code_point(snake: &mut String, c: char) {
+    if !snake.is_empty() && !snake.ends_with('_') {
+        snake.push('_');
     }
+    snake.push_str(&format!("u{:04x}", c as u32));
 }
 
-// https://discord.com/developers/docs/interactions/slash-commands#registering-a-command
-const MAX_LEN: usize = 32;
-#[derive(Clone)]
-struct Buffer {
-    buf: [u8; MAX_LEN],
-    len: usize,
+// discord accepts letters and digits from any script, along with the marks of devanagari and thai, as long as
+// they're lowercase wherever case exists; names made up of only `-` and `_` are refused since there's nothing to
+// name the generated items after
+// https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-naming
+pub(crate) fn validate(s: &str) -> bool {
+    (1..=MAX_LEN).contains(&s.chars().count())
+        && s.chars().any(|c| c != '-' && c != '_')
+        && s.chars().all(|c| match c {
+            'a'..='z' | '0'..='9' | '_' | '-' => true,
+            c if c.is_ascii() => false,
+            c => (c.is_alphanumeric() || is_devanagari_or_thai(c)) && c.to_lowercase().eq(Some(c)),
+        })
 }
 
-impl Buffer {
-    pub const fn new() -> Self {
-        Self {
-            buf: [0_u8; MAX_LEN],
-            len: 0,
-        }
-    }
-    pub fn push(&mut self, b: u8) {
-        self.buf[self.len] = b;
-        self.len += 1;
-    }
-    pub fn extend(&mut self, bytes: &[u8]) {
-        self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
-        self.len += bytes.len();
-    }
+fn is_devanagari_or_thai(c: char) -> bool {
+    matches!(c, '\u{0900}'..='\u{097f}' | '\u{a8e0}'..='\u{a8ff}' | '\u{0e00}'..='\u{0e7f}')
 }
 
-impl Debug for Buffer {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(std::str::from_utf8(&*self).unwrap())
+// https://doc.rust-lang.org/reference/keywords.html
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+    "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// a snake case identifier, which becomes a raw identifier when it's a keyword, e.g. `r#type`; formatting it gives
+/// back the plain name, for building other identifiers out of it
+#[derive(Clone, Copy)]
+pub(crate) struct Ident<'a>(pub &'a str);
+
+impl ToTokens for Ident<'_> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if KEYWORDS.contains(&self.0) {
+            tokens.append(proc_macro2::Ident::new_raw(self.0, Span::call_site()));
+        } else {
+            tokens.append(proc_macro2::Ident::new(self.0, Span::call_site()));
+        }
     }
 }
 
-impl Display for Buffer {
+impl Display for Ident<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        f.write_str(std::str::from_utf8(&*self).unwrap())
+        f.write_str(self.0)
     }
 }
 
-impl Deref for Buffer {
-    type Target = [u8];
-    fn deref(&self) -> &Self::Target {
-        &self.buf[..self.len]
+// NOTE: camel-case might be shorter by a few characters
+impl PartialEq for Name {
+    fn eq(&self, other: &Self) -> bool {
+        self.camel == other.camel
     }
 }
 
-impl DerefMut for Buffer {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.buf[..self.len]
-    }
-}
+// https://discord.com/developers/docs/interactions/slash-commands#registering-a-command
+const MAX_LEN: usize = 32;
+// https://discord.com/developers/docs/interactions/slash-commands#application-command-object-application-command-option-choice-structure
+const MAX_CHOICE_LEN: usize = 100;
@@ -0,0 +1,310 @@
+use crate::{Command, CommandOption, Name, Type, LOCALES};
+use std::collections::BTreeMap;
+
+// https://discord.com/developers/docs/interactions/application-commands#application-command-object-application-command-structure
+const MAX_OPTIONS: usize = 25;
+const MAX_CHOICES: usize = 25;
+const MAX_DESCRIPTION_LEN: usize = 100;
+const MAX_COMMAND_LEN: usize = 4000;
+// a subcommand group holding a subcommand holding options
+const MAX_DEPTH: usize = 3;
+
+// items generated next to the enums of options with choices
+const LEAF_ITEMS: &[&str] = &[
+    "Options", "PartialOptions", "Focused", "Partial", "Property", "Locale", "Resolved", "ChannelType", "UserId",
+    "ChannelId", "RoleId", "MentionableId", "AttachmentId", "User", "PartialMember", "Role", "PartialChannel",
+    "Attachment", "Visitor", "SeqAccess", "Deserializer", "String", "Option", "Result", "Vec",
+];
+// items generated next to the enums of subcommand groups
+const ROOT_ITEMS: &[&str] = &["Options", "AutocompleteOptions", "Unchecked", "Locale"];
+// items generated next to the enums of options pulled in from shared fragments
+const TOP_LEVEL_ITEMS: &[&str] = &[
+    "Interaction", "Ping", "ApplicationCommand", "Command", "Autocomplete", "AutocompleteCommand", "User", "Snowflake",
+    "UserId", "ChannelId", "RoleId", "MentionableId", "AttachmentId", "MessageId", "ChannelType",
+    "InteractionContextType", "Permissions", "Locale", "PartialMember", "Resolved", "Role", "RoleTags", "Attachment",
+    "Message", "PartialChannel",
+];
+
+// checks commands against the limits discord enforces on registration and against names which would clash once
+// turned into identifiers, returning every violation rather than just the first one
+pub(crate) fn validate(commands: &[Command]) -> Result<(), Vec<String>> {
+    let mut violations = Vec::new();
+    check_collisions("commands", commands.iter().map(Command::name), RENAME, &mut violations);
+    for command in commands {
+        validate_command(command, &mut violations);
+    }
+    check_shared_enums(commands, &mut violations);
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+fn validate_command(command: &Command, violations: &mut Vec<String>) {
+    if let Command::ChatInput(root) = command {
+        validate_option(root, &mut vec![root.name.wire()], 0, violations);
+        let len = LOCALES
+            .iter()
+            .map(|&(locale, _)| Some(locale))
+            .chain(Some(None))
+            .map(|locale| command_len(root, locale))
+            .max()
+            .unwrap_or_default();
+        if len > MAX_COMMAND_LEN {
+            violations.push(format!(
+                "{}: names, descriptions and choices add up to {} characters, more than the limit of {}",
+                root.name.wire(),
+                len,
+                MAX_COMMAND_LEN
+            ));
+        }
+        for group in root.options.iter().filter(|x| x.r#type == Some(Type::SubcommandGroup)) {
+            let camel = group.name.camel().0;
+            if ROOT_ITEMS.contains(&camel) || camel == root.name.camel().0 {
+                violations.push(format!(
+                    "{} > {}: the group's enum would be called `{}`, which is already taken; set a `rust_name`",
+                    root.name.wire(),
+                    group.name.wire(),
+                    camel
+                ));
+            }
+        }
+    }
+}
+
+// enums of shared fragments all live at the top level, so two different fragments can't share a name
+fn check_shared_enums(commands: &[Command], violations: &mut Vec<String>) {
+    let mut seen = BTreeMap::new();
+    let options = commands.iter().filter_map(|x| match x {
+        Command::ChatInput(root) => Some(root.shared_enums()),
+        _ => None,
+    });
+    for option in options.flatten() {
+        let (camel, fragment) = (option.name.camel().0, option.fragment.as_ref().map_or("", |x| x.id.as_str()));
+        match seen.insert(camel, fragment) {
+            None if TOP_LEVEL_ITEMS.contains(&camel) => violations.push(format!(
+                "{}: the enum for its choices would be called `{}`, which is already taken; set a `rust_name`",
+                fragment, camel
+            )),
+            Some(other) if other != fragment => violations.push(format!(
+                "{} and {}: the enums for their choices would both be called `{}`; set a `rust_name` on one of them",
+                other, fragment, camel
+            )),
+            _ => {}
+        }
+    }
+}
+
+// how to get out of a collision; choices have no `rust_name` to set
+const RENAME: &str = "rename one of them or set a `rust_name`";
+const RENAME_CHOICE: &str = "rename one of them";
+
+// names which end up as the same identifier, e.g. `foo-bar` and `foo_bar`
+fn check_collisions<'a>(path: &str, names: impl Iterator<Item = &'a Name>, fix: &str, violations: &mut Vec<String>) {
+    let names = names.collect::<Vec<_>>();
+    for (i, a) in names.iter().enumerate() {
+        let collision = names[..i].iter().find_map(|b| {
+            if a.snake().0 == b.snake().0 {
+                Some((b, a.snake().0))
+            } else if a.camel().0 == b.camel().0 {
+                Some((b, a.camel().0))
+            } else {
+                None
+            }
+        });
+        if let Some((b, ident)) = collision {
+            violations.push(format!(
+                "{}: `{}` and `{}` would both be called `{}`; {}",
+                path,
+                b.wire(),
+                a.wire(),
+                ident,
+                fix
+            ));
+        }
+    }
+}
+
+fn validate_option<'a>(option: &'a CommandOption, path: &mut Vec<&'a str>, depth: usize, violations: &mut Vec<String>) {
+    validate_structure(option, &path.join(" > "), violations);
+    validate_values(option, &path.join(" > "), violations);
+    check_collisions(&path.join(" > "), option.options.iter().map(|x| &x.name), RENAME, violations);
+    if !option.choices.is_empty() {
+        check_collisions(&path.join(" > "), option.choices.iter().map(|x| &x.name), RENAME_CHOICE, violations);
+        if LEAF_ITEMS.contains(&option.name.camel().0) {
+            violations.push(format!(
+                "{}: the enum for its choices would be called `{}`, which is already taken; set a `rust_name`",
+                path.join(" > "),
+                option.name.camel()
+            ));
+        }
+    }
+    // `PartialOptions` holds the focused option's value next to the ones for each option
+    if option.options.iter().any(|x| x.autocomplete) {
+        if let Some(x) = option.options.iter().find(|x| x.name.snake().0 == "focused") {
+            violations.push(format!(
+                "{} > {}: options of commands with autocomplete can't be called `focused`; set a `rust_name`",
+                path.join(" > "),
+                x.name.wire()
+            ));
+        }
+    }
+    let mut report = |message: String| violations.push(format!("{}: {}", path.join(" > "), message));
+
+    let descriptions = Some(option.description.as_str()).into_iter().chain(option.description_localizations.values().map(String::as_str));
+    for description in descriptions {
+        let len = description.chars().count();
+        if !(1..=MAX_DESCRIPTION_LEN).contains(&len) {
+            report(format!("description {:?} must be 1-{} characters long", description, MAX_DESCRIPTION_LEN));
+        }
+    }
+    if option.choices.len() > MAX_CHOICES {
+        report(format!("has {} choices, more than the limit of {}", option.choices.len(), MAX_CHOICES));
+    }
+    if option.options.len() > MAX_OPTIONS {
+        report(format!("has {} options, more than the limit of {}", option.options.len(), MAX_OPTIONS));
+    }
+    if let Some(x) = option.options.iter().skip_while(|x| x.required).find(|x| x.required) {
+        report(format!("required option `{}` comes after an optional one", x.name.wire()));
+    }
+    if depth > MAX_DEPTH {
+        report(format!("is nested {} levels deep, more than the limit of {}", depth, MAX_DEPTH));
+        return;
+    }
+    for x in &option.options {
+        path.push(x.name.wire());
+        validate_option(x, path, depth + 1, violations);
+        path.pop();
+    }
+}
+
+// the root holds either subcommands and groups or plain options, groups hold subcommands, and subcommands hold
+// plain options; anything else either fails to generate or doesn't match what discord sends
+fn validate_structure(option: &CommandOption, path: &str, violations: &mut Vec<String>) {
+    let is_subcommand = |x: &CommandOption| matches!(x.r#type, Some(Type::Subcommand | Type::SubcommandGroup));
+    match option.r#type {
+        None => {
+            if option.options.iter().any(is_subcommand) {
+                for x in option.options.iter().filter(|x| !is_subcommand(x)) {
+                    violations.push(format!(
+                        "{} > {}: plain options cannot sit next to subcommands; move it into a subcommand",
+                        path,
+                        x.name.wire()
+                    ));
+                }
+            }
+        }
+        Some(Type::SubcommandGroup) => {
+            for x in &option.options {
+                let message = match x.r#type {
+                    Some(Type::Subcommand) => continue,
+                    Some(Type::SubcommandGroup) => "subcommand groups cannot be nested",
+                    _ => "subcommand groups may only contain subcommands",
+                };
+                violations.push(format!("{} > {}: {}", path, x.name.wire(), message));
+            }
+        }
+        Some(Type::Subcommand) => {
+            for x in option.options.iter().filter(|x| is_subcommand(x)) {
+                violations.push(format!(
+                    "{} > {}: subcommands may only contain plain options, not subcommands or groups",
+                    path,
+                    x.name.wire()
+                ));
+            }
+        }
+        Some(_) => {
+            if !option.options.is_empty() {
+                violations.push(format!("{}: only subcommands and subcommand groups may have options", path));
+            }
+        }
+    }
+}
+
+// choices and bounds end up compared against values of the option's type, so they have to be of that type too
+fn validate_values(option: &CommandOption, path: &str, violations: &mut Vec<String>) {
+    let Some(kind) = &option.r#type else { return };
+    let bounds = [("min_value", &option.min_value), ("max_value", &option.max_value)];
+    for (key, bound) in bounds.iter().filter_map(|&(key, x)| Some((key, x.as_ref()?))) {
+        if !matches!(kind, Type::Integer | Type::Number) {
+            violations.push(format!("{}: {} is only allowed on INTEGER and NUMBER options, not {}", path, key, type_name(kind)));
+        } else if !is_value_of(kind, &serde_json::Value::Number(bound.clone())) {
+            violations.push(format!("{}: {} is {}, which isn't a valid {}", path, key, bound, type_name(kind)));
+        }
+    }
+    let lengths = [("min_length", option.min_length), ("max_length", option.max_length)];
+    for (key, _) in lengths.iter().filter(|(_, x)| x.is_some()) {
+        if kind != &Type::String {
+            violations.push(format!("{}: {} is only allowed on STRING options, not {}", path, key, type_name(kind)));
+        }
+    }
+    if option.choices.is_empty() {
+        return;
+    }
+    if !matches!(kind, Type::String | Type::Integer | Type::Number) {
+        violations.push(format!("{}: choices are only allowed on STRING, INTEGER and NUMBER options, not {}", path, type_name(kind)));
+        return;
+    }
+    for choice in &option.choices {
+        if !is_value_of(kind, &choice.value) {
+            violations.push(format!(
+                "{}: choice `{}` has the value {}, which isn't a valid {}",
+                path,
+                choice.name.wire(),
+                choice.value,
+                type_name(kind)
+            ));
+        }
+    }
+}
+
+fn is_value_of(kind: &Type, value: &serde_json::Value) -> bool {
+    match kind {
+        Type::String => value.is_string(),
+        Type::Integer => value.is_i64(),
+        Type::Number => value.is_number(),
+        _ => false,
+    }
+}
+
+// the names discord's documentation uses for option types
+fn type_name(kind: &Type) -> &'static str {
+    match kind {
+        Type::String => "STRING",
+        Type::Bool => "BOOLEAN",
+        Type::Integer => "INTEGER",
+        Type::Number => "NUMBER",
+        Type::User => "USER",
+        Type::Channel => "CHANNEL",
+        Type::Role => "ROLE",
+        Type::Mentionable => "MENTIONABLE",
+        Type::Attachment => "ATTACHMENT",
+        Type::Subcommand => "SUB_COMMAND",
+        Type::SubcommandGroup => "SUB_COMMAND_GROUP",
+    }
+}
+
+// discord counts the name, description and choices of a command and all of its options, in whichever locale
+// comes out the longest
+fn command_len(option: &CommandOption, locale: Option<&str>) -> usize {
+    let localized = |default: &str, localizations: &BTreeMap<String, String>| {
+        locale.and_then(|x| localizations.get(x)).map_or(default, String::as_str).chars().count()
+    };
+    let choices: usize = option
+        .choices
+        .iter()
+        .map(|x| {
+            let value = match &x.value {
+                serde_json::Value::String(x) => x.chars().count(),
+                x => x.to_string().len(),
+            };
+            localized(x.name.wire(), &x.name_localizations) + value
+        })
+        .sum();
+    let options: usize = option.options.iter().map(|x| command_len(x, locale)).sum();
+    localized(option.name.wire(), &option.name_localizations)
+        + localized(&option.description, &option.description_localizations)
+        + choices
+        + options
+}
@@ -13,7 +13,7 @@ pub fn typify(input: TokenStream) -> TokenStream {
             .parse(input)
             .unwrap()
             .into_iter()
-            .map(|x| std::fs::read_to_string(x.value()).expect("provided file should be readable")),
+            .map(|x| discord_typed_interactions_lib::Schema::read(x.value()).expect("provided file should be readable")),
         None,
     )
     .into()
@@ -2,6 +2,7 @@ use discord_typed_interactions::Configuration;
 
 fn main() {
     Configuration::new("schema/ctf.json")
+        .src("schema/greet.yaml")
         .dest(std::env::var("OUT_DIR").unwrap() + "/ctf_gen.rs")
         .watch_schema()
         .generate();
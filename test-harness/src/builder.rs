@@ -1,9 +1,38 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
 mod included {
     include!(concat!(env!("OUT_DIR"), "/ctf_gen.rs"));
 }
-use included::ctf;
-use ctf::Options;
+use included::{ctf, greet};
+use serde_json::json;
 
 fn main() {
+    let play = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"play",
+          "options":[
+             {
+                "name":"name",
+                "value":"howdy"
+             }
+          ]
+       }
+    ]
+    });
+    serde_json::from_value::<ctf::Ctf>(play).unwrap();
 
-}
\ No newline at end of file
+    let greet = json!({
+    "id":"868983602015252520",
+    "name":"greet",
+    "options":[
+        {
+            "name":"who",
+            "value":"174667467509989376"
+        }
+    ]
+    });
+    serde_json::from_value::<greet::Greet>(greet).unwrap();
+}
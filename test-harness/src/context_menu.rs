@@ -0,0 +1,98 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
+use discord_typed_interactions::typify;
+use serde_json::json;
+
+typify!(
+    "./test-harness/schema/report_user.json",
+    "./test-harness/schema/quote_message.json",
+    "./test-harness/schema/no_subcommands.json"
+);
+
+fn main() {
+    let report = json!({
+    "id":"868983602015252520",
+    "name":"Report User",
+    "type":2,
+    "target_id":"80351110224678912",
+    "resolved":{
+        "users":{
+            "80351110224678912":{
+                "avatar":"a_662952cca2d45e446f0ccd6fe58f7453",
+                "discriminator":"0001",
+                "id":"80351110224678912",
+                "username":"mason"
+            }
+        },
+        "members":{
+            "80351110224678912":{
+                "joined_at":"2018-01-10T22:44:05.797000+00:00",
+                "roles":["41771983423143936"]
+            }
+        }
+    }
+    });
+    let report = serde_json::from_value::<report_user::ReportUser>(report).unwrap();
+    assert_eq!(report.target_id, UserId(Snowflake(80351110224678912)));
+    assert_eq!(report.target_user().unwrap().id, report.target_id);
+    assert_eq!(report.target_member().unwrap().roles, [RoleId(Snowflake(41771983423143936))]);
+    assert_eq!(report_user::ReportUser::localized_name(Locale::Fr), "Signaler");
+    assert_eq!(report_user::ReportUser::localized_name(Locale::De), "Report User");
+    assert_eq!(report_user::ReportUser::DEFAULT_MEMBER_PERMISSIONS, None);
+    const { assert!(report_user::ReportUser::DM_PERMISSION) };
+    assert_eq!(report_user::ReportUser::CONTEXTS, None);
+
+    let quote = json!({
+    "id":"868983602015252521",
+    "name":"Quote",
+    "type":3,
+    "target_id":"1001",
+    "resolved":{
+        "messages":{
+            "1001":{
+                "id":"1001",
+                "channel_id":"41771983423143937",
+                "author":{
+                    "avatar":"a_662952cca2d45e446f0ccd6fe58f7453",
+                    "discriminator":"0001",
+                    "id":"80351110224678912",
+                    "username":"mason"
+                },
+                "content":"hello",
+                "timestamp":"2021-07-28T00:00:00.000000+00:00",
+                "edited_timestamp":null,
+                "tts":false,
+                "mention_everyone":false,
+                "pinned":false,
+                "type":0
+            }
+        }
+    }
+    });
+    let interaction = serde_json::from_value::<Interaction>(json!({
+        "type":2,
+        "id":"868983602015252523",
+        "application_id":"868983602015252524",
+        "data":quote,
+        "channel_id":"41771983423143937",
+        "token":"token",
+        "version":1
+    }));
+    let quote = match interaction {
+        Ok(Interaction::ApplicationCommand(ApplicationCommand { data: Command::Quote(quote), .. })) => quote,
+        other => panic!("expected the quote command, got {:?}", other),
+    };
+    assert_eq!(quote.target_message().unwrap().content, "hello");
+
+    // a command sharing the structure of another one is still told apart by its name
+    let other = json!({
+    "id":"868983602015252522",
+    "name":"Bookmark",
+    "type":3,
+    "target_id":"1001"
+    });
+    match serde_json::from_value::<Command>(other).unwrap() {
+        Command::Other { name, .. } => assert_eq!(name, "Bookmark"),
+        other => panic!("expected an unknown command, got {:?}", other),
+    }
+}
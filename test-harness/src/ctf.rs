@@ -1,3 +1,5 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
 use discord_typed_interactions::typify;
 use serde_json::json;
 
@@ -21,6 +23,369 @@ fn main() {
     });
     serde_json::from_value::<ctf::Ctf>(play).unwrap();
 
+    let play = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"play",
+          "options":[
+             {
+                "name":"name",
+                "value":"howdy"
+             },
+             {
+                "name":"メモ",
+                "value":"bring snacks"
+             },
+             {
+                "name":"ノート",
+                "value":"and drinks"
+             }
+          ]
+       }
+    ]
+    });
+    let play = match serde_json::from_value::<ctf::Ctf>(play).unwrap().options {
+        ctf::Options::Play(play) => play,
+        _ => panic!("deserialized into a subcommand that is not Play"),
+    };
+    assert_eq!(play.memo.as_deref(), Some("bring snacks"));
+    assert_eq!(play.u30ce_u30fc_u30c8.as_deref(), Some("and drinks"));
+
+    let long_note = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"play",
+          "options":[
+             {
+                "name":"name",
+                "value":"howdy"
+             },
+             {
+                "name":"ノート",
+                "value":"bring snacks and drinks"
+             }
+          ]
+       }
+    ]
+    });
+    let err = serde_json::from_value::<ctf::Ctf>(long_note).unwrap_err();
+    // errors name options the way they're written in the schema, not by their identifier
+    assert!(err.to_string().contains("`ノート` must be at most 20 characters long"), "{}", err);
+
+    let add_many = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"players",
+          "options":[
+             {
+                "name":"add-many",
+                "options":[
+                   {
+                      "name":"first-player",
+                      "value":"174667467509989376"
+                   }
+                ]
+             }
+          ]
+       }
+    ]
+    });
+    let parsed = serde_json::from_value::<ctf::Ctf>(add_many.clone()).unwrap();
+    match &parsed.options {
+        ctf::Options::Players(ctf::players::Players::AddMany(add_many)) => {
+            assert_eq!(add_many.first_player, UserId(Snowflake(174667467509989376)));
+            assert_eq!(add_many.second_player, None);
+        }
+        _ => panic!("deserialized into a subcommand that is not AddMany"),
+    }
+    let serialized = serde_json::to_value(&parsed).unwrap();
+    assert_eq!(serialized["name"], "ctf");
+    assert_eq!(serialized["options"]["name"], "players");
+    assert_eq!(serialized["options"]["options"]["name"], "add-many");
+
+    let archive_all = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"archive-all",
+          "type":1
+       }
+    ]
+    });
+    let parsed = serde_json::from_value::<ctf::Ctf>(archive_all).unwrap();
+    assert!(matches!(parsed.options, ctf::Options::ArchiveAll));
+
+    let clear = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"players",
+          "options":[
+             {
+                "name":"clear",
+                "options":[]
+             }
+          ]
+       }
+    ]
+    });
+    let parsed = serde_json::from_value::<ctf::Ctf>(clear).unwrap();
+    assert!(matches!(parsed.options, ctf::Options::Players(ctf::players::Players::Clear)));
+
+    let solve = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"solve",
+          "options":[
+             {
+                "name":"flag",
+                "value":"flag{howdy}"
+             },
+             {
+                "name":"points",
+                "value":-50
+             },
+             {
+                "name":"multiplier",
+                "value":1.5
+             }
+          ]
+       }
+    ]
+    });
+    match serde_json::from_value::<ctf::Ctf>(solve).unwrap().options {
+        ctf::Options::Solve(solve) => {
+            assert_eq!(solve.flag, "flag{howdy}");
+            assert_eq!(solve.points, Some(-50));
+            assert_eq!(solve.channel, None);
+            assert_eq!(solve.multiplier, Some(1.5));
+        }
+        _ => panic!("deserialized into an options variant that is not Solve"),
+    }
+
+    let integral_multiplier = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"solve",
+          "options":[
+             {
+                "name":"flag",
+                "value":"flag{howdy}"
+             },
+             {
+                "name":"multiplier",
+                "value":2
+             }
+          ]
+       }
+    ]
+    });
+    match serde_json::from_value::<ctf::Ctf>(integral_multiplier).unwrap().options {
+        ctf::Options::Solve(solve) => assert_eq!(solve.multiplier, Some(2.0)),
+        _ => panic!("deserialized into an options variant that is not Solve"),
+    }
+
+    let out_of_range = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"solve",
+          "options":[
+             {
+                "name":"flag",
+                "value":"flag{howdy}"
+             },
+             {
+                "name":"multiplier",
+                "value":10.5
+             }
+          ]
+       }
+    ]
+    });
+    let err = serde_json::from_value::<ctf::Ctf>(out_of_range).unwrap_err();
+    assert!(err.to_string().contains("multiplier"));
+
+    let short_flag = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"solve",
+          "options":[
+             {
+                "name":"flag",
+                "value":"flag"
+             }
+          ]
+       }
+    ]
+    });
+    let err = serde_json::from_value::<ctf::Ctf>(short_flag).unwrap_err();
+    assert!(err.to_string().contains("flag"));
+    assert_eq!(ctf::solve::Options::FLAG_MIN_LENGTH, 6);
+    assert_eq!(ctf::solve::Options::FLAG_MAX_LENGTH, 100);
+
+    let archive = |channel_type: u64| {
+        json!({
+        "id":"868983602015252520",
+        "name":"ctf",
+        "options":[
+           {
+              "name":"archive",
+              "options":[
+                 {
+                    "name":"channel",
+                    "value":"837704702590058507"
+                 }
+              ]
+           }
+        ],
+        "resolved":{
+           "channels":{
+              "837704702590058507":{
+                 "id":"837704702590058507",
+                 "name":"general",
+                 "permissions":"274877906943",
+                 "type":channel_type
+              }
+           }
+        }
+        })
+    };
+    let parsed = serde_json::from_value::<ctf::Ctf>(archive(4)).unwrap();
+    assert_eq!(
+        parsed.resolved.unwrap().channels[&ChannelId(Snowflake(837704702590058507))].r#type,
+        ChannelType::GuildCategory
+    );
+    let err = serde_json::from_value::<ctf::Ctf>(archive(2)).unwrap_err();
+    assert!(err.to_string().contains("channel"));
+    assert_eq!(
+        ctf::archive::Options::CHANNEL_CHANNEL_TYPES,
+        &[ChannelType::GuildText, ChannelType::GuildCategory]
+    );
+
+    let writeup = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"solve",
+          "options":[
+             {
+                "name":"flag",
+                "value":"flag{howdy}"
+             },
+             {
+                "name":"writeup",
+                "value":"1141142516009959504"
+             }
+          ]
+       }
+    ],
+    "resolved":{
+       "attachments":{
+          "1141142516009959504":{
+             "id":"1141142516009959504",
+             "filename":"writeup.md",
+             "size":1024,
+             "url":"https://cdn.discordapp.com/ephemeral-attachments/1/1141142516009959504/writeup.md",
+             "proxy_url":"https://media.discordapp.net/ephemeral-attachments/1/1141142516009959504/writeup.md",
+             "content_type":"text/markdown; charset=utf-8",
+             "ephemeral":true
+          }
+       }
+    }
+    });
+    let parsed = serde_json::from_value::<ctf::Ctf>(writeup).unwrap();
+    let solve = match parsed.options {
+        ctf::Options::Solve(solve) => solve,
+        _ => panic!("deserialized into an options variant that is not Solve"),
+    };
+    let resolved = parsed.resolved.unwrap();
+    let attachment = &resolved.attachments[&solve.writeup.unwrap()];
+    assert_eq!(attachment.filename, "writeup.md");
+    assert_eq!(attachment.size, 1024);
+    assert_eq!(solve.writeup_attachment(&resolved).unwrap().id, attachment.id);
+    assert!(solve.channel_channel(&resolved).is_none());
+
+    let chall = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"chall",
+          "options":[
+             {
+                "name":"name",
+                "value":"baby-rop"
+             },
+             {
+                "name":"category",
+                "value":"rev"
+             },
+             {
+                "name":"difficulty",
+                "value":2
+             }
+          ]
+       }
+    ]
+    });
+    match serde_json::from_value::<ctf::Ctf>(chall).unwrap().options {
+        ctf::Options::Chall(chall) => {
+            assert_eq!(chall.category, Some(ctf::chall::Category::ReverseEngineering));
+            assert_eq!(chall.difficulty, Some(ctf::chall::Difficulty::Hard));
+        }
+        _ => panic!("deserialized into an options variant that is not Chall"),
+    }
+
+    let invalid_choice = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"chall",
+          "options":[
+             {
+                "name":"name",
+                "value":"baby-rop"
+             },
+             {
+                "name":"category",
+                "value":"crypto"
+             }
+          ]
+       }
+    ]
+    });
+    serde_json::from_value::<ctf::Ctf>(invalid_choice).unwrap_err();
+
+    let missing_required = json!({
+    "id":"868983602015252520",
+    "name":"ctf",
+    "options":[
+       {
+          "name":"play",
+          "options":[]
+       }
+    ]
+    });
+    serde_json::from_value::<ctf::Ctf>(missing_required).unwrap_err();
+
     let players_add = json!({
        "id":"868983602015252520",
        "name":"ctf",
@@ -62,11 +427,21 @@ fn main() {
        }
     });
     let parsed = serde_json::from_value::<ctf::Ctf>(players_add).unwrap();
-    match parsed.options {
-        ctf::Options::Players(_) => {}
-        _ => panic!("deserialized into an options variant that is not Players"),
-    }
-    parsed.resolved.unwrap();
+    let add = match parsed.options {
+        ctf::Options::Players(ctf::players::Players::Add(add)) => add,
+        _ => panic!("deserialized into an options variant that is not Players::Add"),
+    };
+    let name = add.name;
+    assert_eq!(name, MentionableId(Snowflake(174667467509989376)));
+    let resolved = parsed.resolved.unwrap();
+    assert_eq!(resolved.users[&name.as_user()].username, "sky");
+    assert_eq!(add.name_user(&resolved).unwrap().username, "sky");
+    assert!(add.name_member(&resolved).is_some());
+    assert!(add.name_role(&resolved).is_none());
+    assert_eq!(
+        resolved.members[&name.as_user()].roles,
+        vec![RoleId(Snowflake(868920975901736991))]
+    );
 
     let interaction = json!({
        "application_id":"867561056485769226",
@@ -116,5 +491,81 @@ fn main() {
        "type":2,
        "version":1
     });
-    serde_json::from_value::<Interaction>(interaction).unwrap();
+    match serde_json::from_value::<Interaction>(interaction).unwrap() {
+        Interaction::ApplicationCommand(ApplicationCommand { member: Some(member), .. }) => {
+            let permissions = member.permissions.unwrap();
+            assert!(ctf::Ctf::DEFAULT_MEMBER_PERMISSIONS.is_none_or(|x| permissions.contains(x)));
+        }
+        _ => panic!("expected an application command sent by a member"),
+    }
+
+    let autocomplete = json!({
+       "application_id":"867561056485769226",
+       "channel_id":"837704702590058507",
+       "data":{
+          "id":"868983602015252520",
+          "name":"ctf",
+          "options":[
+             {
+                "name":"chall",
+                "options":[
+                   {
+                      "name":"category",
+                      "type":3,
+                      "value":"web"
+                   },
+                   {
+                      "name":"name",
+                      "type":3,
+                      "value":"baby",
+                      "focused":true
+                   }
+                ],
+                "type":1
+             }
+          ],
+          "type":1
+       },
+       "guild_id":"400781877629419520",
+       "id":"873763819476893747",
+       "token":"lol no",
+       "type":4,
+       "version":1
+    });
+    let autocomplete = match serde_json::from_value::<Interaction>(autocomplete).unwrap() {
+        Interaction::Autocomplete(x) => x,
+        _ => panic!("deserialized into an interaction variant that is not Autocomplete"),
+    };
+    match autocomplete.data {
+        AutocompleteCommand::Ctf(ctf::CtfAutocomplete {
+            options: ctf::AutocompleteOptions::Chall(chall),
+            ..
+        }) => {
+            assert_eq!(chall.focused, ctf::chall::Focused::Name("baby".to_string()));
+            assert_eq!(chall.category, Some(ctf::chall::Category::Web));
+            assert_eq!(chall.name, None);
+        }
+        _ => panic!("deserialized into an autocomplete variant that is not Chall"),
+    }
+
+    assert_eq!(ctf::Ctf::localized_name(Locale::Fr), "ctf");
+    assert_eq!(ctf::play::Options::localized_name(Locale::Ja), "プレイ");
+    assert_eq!(ctf::play::Options::localized_name(Locale::De), "play");
+    assert_eq!(ctf::play::Options::localized_description(Locale::Fr), "espace réservé");
+    assert_eq!(ctf::play::Options::name_localized_name(Locale::Fr), "nom");
+    assert_eq!(ctf::players::Players::localized_description(Locale::Fr), "placeholder");
+    assert_eq!(ctf::Options::archive_all_localized_name(Locale::Fr), "archive-all");
+    assert_eq!(ctf::Options::archive_all_localized_description(Locale::Fr), "archives every ctf");
+    assert_eq!(ctf::players::Players::clear_localized_name(Locale::De), "clear");
+    assert_eq!(
+        ctf::chall::Category::ReverseEngineering.localized_name(Locale::Fr),
+        "Rétro-ingénierie"
+    );
+    assert_eq!(ctf::chall::Category::Web.localized_name(Locale::Fr), "Web");
+
+    assert_eq!(ctf::Ctf::DEFAULT_MEMBER_PERMISSIONS, Some(Permissions::MANAGE_GUILD));
+    assert!(!Permissions::MANAGE_GUILD.contains(Permissions::MANAGE_GUILD | Permissions::BAN_MEMBERS));
+    const { assert!(!ctf::Ctf::DM_PERMISSION) };
+    assert_eq!(ctf::Ctf::CONTEXTS, Some(&[InteractionContextType::Guild][..]));
+    const { assert!(!ctf::Ctf::NSFW) };
 }
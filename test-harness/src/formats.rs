@@ -0,0 +1,43 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
+use discord_typed_interactions::typify;
+use serde_json::json;
+
+typify!("./test-harness/schema/greet.yaml", "./test-harness/schema/poll.toml");
+
+fn main() {
+    let greet = json!({
+    "id":"868983602015252520",
+    "name":"greet",
+    "options":[
+        {
+            "name":"who",
+            "value":"174667467509989376"
+        },
+        {
+            "name":"greeting",
+            "value":"good-morning"
+        }
+    ]
+    });
+    let greet = serde_json::from_value::<greet::Greet>(greet).unwrap();
+    assert_eq!(greet.options.who, UserId(Snowflake(174667467509989376)));
+    assert_eq!(greet.options.greeting, Some(greet::greet::Greeting::GoodMorning));
+
+    let poll = json!({
+    "id":"868983602015252521",
+    "name":"poll",
+    "options":[
+        {
+            "name":"question",
+            "value":"pizza?"
+        },
+        {
+            "name":"minutes",
+            "value":5000
+        }
+    ]
+    });
+    serde_json::from_value::<poll::Poll>(poll).unwrap_err();
+    assert_eq!(poll::Poll::DEFAULT_MEMBER_PERMISSIONS, Some(Permissions::MANAGE_MESSAGES));
+}
@@ -0,0 +1,85 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
+use discord_typed_interactions::typify;
+use serde_json::json;
+
+typify!("./test-harness/schema/moderate.json", "./test-harness/schema/appeal.json");
+
+fn main() {
+    let ban = json!({
+    "id":"868983602015252520",
+    "name":"moderate",
+    "options":[
+       {
+          "name":"ban",
+          "options":[
+             {
+                "name":"member",
+                "value":"174667467509989376"
+             },
+             {
+                "name":"reason",
+                "value":"spam"
+             },
+             {
+                "name":"days",
+                "value":7
+             }
+          ]
+       }
+    ]
+    });
+    match serde_json::from_value::<moderate::Moderate>(ban).unwrap().options {
+        moderate::Options::Ban(ban) => {
+            assert_eq!(ban.member, UserId(Snowflake(174667467509989376)));
+            assert_eq!(ban.reason, Reason::Spam);
+            assert_eq!(ban.days, Some(7));
+        }
+        _ => panic!("deserialized into a subcommand that is not Ban"),
+    }
+
+    let warn = json!({
+    "id":"868983602015252520",
+    "name":"moderate",
+    "options":[
+       {
+          "name":"warn",
+          "options":[
+             {
+                "name":"add",
+                "options":[
+                   {
+                      "name":"member",
+                      "value":"174667467509989376"
+                   },
+                   {
+                      "name":"reason",
+                      "value":"harassment"
+                   }
+                ]
+             }
+          ]
+       }
+    ]
+    });
+    match serde_json::from_value::<moderate::Moderate>(warn).unwrap().options {
+        moderate::Options::Warn(moderate::warn::Warn::Add(add)) => assert_eq!(add.reason, Some(Reason::Harassment)),
+        _ => panic!("deserialized into a subcommand that is not Add"),
+    }
+
+    let appeal = json!({
+    "id":"868983602015252520",
+    "name":"appeal",
+    "options":[
+       {
+          "name":"reason",
+          "value":"other"
+       }
+    ]
+    });
+    let appeal = serde_json::from_value::<appeal::Appeal>(appeal).unwrap();
+    // every schema referencing the fragment shares the one enum
+    let reason: Reason = appeal.options.reason;
+    assert_eq!(reason, Reason::Other);
+    assert_eq!(Reason::Other.localized_name(Locale::Fr), "other");
+}
@@ -1,7 +1,14 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
 use discord_typed_interactions::typify;
 use serde_json::json;
 
-typify!("./test-harness/schema/ctf.json", "./test-harness/schema/no_subcommands.json");
+typify!(
+    "./test-harness/schema/ctf.json",
+    "./test-harness/schema/no_subcommands.json",
+    "./test-harness/schema/bulk.json",
+    "./test-harness/schema/export.json"
+);
 
 
 fn main() {
@@ -41,4 +48,50 @@ fn main() {
     ]
     });
     serde_json::from_value::<test::Test>(test).unwrap();
-}
\ No newline at end of file
+
+    let echo = json!({
+    "id":"868983602015252520",
+    "name":"echo",
+    "options":[
+        {
+            "name":"text",
+            "value":"hello"
+        }
+    ]
+    });
+    match serde_json::from_value::<Command>(echo).unwrap() {
+        Command::Echo(echo) => assert_eq!(echo.options.text, "hello"),
+        other => panic!("expected the echo command, got {:?}", other),
+    }
+
+    // discord leaves `options` out entirely for commands without any
+    let ping = json!({
+    "id":"868983602015252522",
+    "name":"ping"
+    });
+    match serde_json::from_value::<Command>(ping).unwrap() {
+        Command::Ping(ping) => assert_eq!(ping.id, "868983602015252522"),
+        other => panic!("expected the ping command, got {:?}", other),
+    }
+    serde_json::from_value::<ping::Ping>(json!({ "id":"1", "name":"ping", "options":[] })).unwrap();
+    serde_json::from_value::<ping::Ping>(json!({ "id":"1", "name":"ping", "options":[{ "name":"x", "value":1 }] })).unwrap_err();
+
+    let bookmark = json!({
+    "id":"868983602015252521",
+    "name":"Bookmark",
+    "type":3,
+    "target_id":"1001"
+    });
+    match serde_json::from_value::<Command>(bookmark).unwrap() {
+        Command::Bookmark(bookmark) => assert_eq!(bookmark.target_id, MessageId(Snowflake(1001))),
+        other => panic!("expected the bookmark command, got {:?}", other),
+    }
+
+    assert_eq!(roll::Roll::ID, Some(Snowflake(1107021540390633502)));
+    assert_eq!(roll::Roll::APPLICATION_ID, Some(Snowflake(867561056485769226)));
+    assert_eq!(roll::Roll::VERSION, Some(Snowflake(1107021540390633503)));
+    assert_eq!(roll::Roll::GUILD_ID, Some(Snowflake(400781877629419520)));
+    assert_eq!(wave::Wave::VERSION, Some(Snowflake(1107021540390633505)));
+    assert_eq!(wave::Wave::GUILD_ID, None);
+    assert_eq!(echo::Echo::ID, None);
+}
@@ -1,3 +1,5 @@
+#![allow(dead_code, clippy::large_enum_variant)]
+
 use discord_typed_interactions::typify;
 use serde_json::json;
 
@@ -23,4 +25,59 @@ fn main() {
     ]
     });
     serde_json::from_value::<test::Test>(test).unwrap();
+
+    let keywords = json!({
+    "id":"868983602015252520",
+    "name":"test",
+    "options":[
+        {
+            "name":"type",
+            "value":3
+        },
+        {
+            "name":"self",
+            "value":"first"
+        },
+        {
+            "name":"1st",
+            "value":true
+        }
+    ]
+    });
+    let keywords = serde_json::from_value::<test::Test>(keywords).unwrap().options;
+    assert_eq!(keywords.r#type, Some(3));
+    assert_eq!(keywords.self_, Some(test::test::Self_::_1stPlace));
+    assert_eq!(keywords._1st, Some(true));
+
+    let autocomplete = json!({
+    "id":"868983602015252520",
+    "name":"test",
+    "options":[
+        {
+            "name":"a",
+            "value":"a",
+            "focused":true
+        },
+        {
+            "name":"b",
+            "value":"b"
+        }
+    ]
+    });
+    let autocomplete = serde_json::from_value::<test::TestAutocomplete>(autocomplete).unwrap();
+    assert_eq!(autocomplete.options.focused, test::test::Focused::A("a".to_string()));
+    assert_eq!(autocomplete.options.b.as_deref(), Some("b"));
+
+    let unfocusable = json!({
+    "id":"868983602015252520",
+    "name":"test",
+    "options":[
+        {
+            "name":"b",
+            "value":"b",
+            "focused":true
+        }
+    ]
+    });
+    serde_json::from_value::<test::TestAutocomplete>(unfocusable).unwrap_err();
 }
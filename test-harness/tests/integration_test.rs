@@ -33,3 +33,21 @@ fn builder() -> Result<(), std::io::Error> {
     assert_eq!(run_test_harness("builder")?, Some(0));
     Ok(())
 }
+
+#[test]
+fn context_menu() -> Result<(), std::io::Error> {
+    assert_eq!(run_test_harness("context_menu")?, Some(0));
+    Ok(())
+}
+
+#[test]
+fn formats() -> Result<(), std::io::Error> {
+    assert_eq!(run_test_harness("formats")?, Some(0));
+    Ok(())
+}
+
+#[test]
+fn fragments() -> Result<(), std::io::Error> {
+    assert_eq!(run_test_harness("fragments")?, Some(0));
+    Ok(())
+}
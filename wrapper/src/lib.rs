@@ -3,7 +3,7 @@ pub use discord_typed_interactions_proc_macro::typify;
 
 #[cfg(feature = "builder")]
 pub mod export {
-    use discord_typed_interactions_lib::typify_driver;
+    use discord_typed_interactions_lib::{typify_driver, Schema};
     use std::io::Write;
     use std::path::PathBuf;
     use std::process::{Command, Stdio};
@@ -53,6 +53,9 @@ pub mod export {
             self
         }
 
+        /// uses `resolved` in place of the generated `Resolved`; channel options limited by `channel_types` are then
+        /// no longer checked against the channel's type, as there's no telling where to find it, but their
+        /// `*_CHANNEL_TYPES` constants are still generated
         pub fn resolved_struct(&mut self, resolved: impl Into<String>) -> &mut Self {
             self.resolved_struct = Some(resolved.into());
             self
@@ -61,13 +64,17 @@ pub mod export {
         pub fn watch_schema(&mut self) -> &mut Self {
             for i in self.src.as_slice() {
                 println!("cargo:rerun-if-changed={}", i.display());
-
+                // fragments pulled in through `$ref` are part of the schema too
+                let dependencies = Schema::read(i).map(|x| x.dependencies()).unwrap_or_default();
+                for dependency in dependencies {
+                    println!("cargo:rerun-if-changed={}", dependency.display());
+                }
             }
             self
         }
 
         pub fn generate(&self) {
-            let schema_contents = self.src.iter().map(|x| std::fs::read_to_string(x).unwrap());
+            let schema_contents = self.src.iter().map(|x| Schema::read(x).unwrap());
             let rust_source =
                 typify_driver(schema_contents, self.resolved_struct.as_deref())
                     .to_string();